@@ -0,0 +1,103 @@
+//! The AUR helpers wrap pacman, so most of the operations are the same for
+//! all of them. They differ in how they list the outdated packages.
+
+use crate::{cmd::command_exists, config::Config};
+
+use tokio::process::Command;
+
+/// The helpers that are detected, in order of preference
+const KNOWN: [&str; 4] = ["yay", "paru", "pikaur", "trizen"];
+/// Suffixes of the packages built from a version control system
+const VCS_SUFFIXES: [&str; 5] = ["-git", "-svn", "-hg", "-bzr", "-darcs"];
+
+/// All the helpers separated by `|`, as a dependency in the registry
+pub const DEPENDENCY: &str = "yay|paru|pikaur|trizen";
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AurHelper {
+    Yay,
+    Paru,
+    Pikaur,
+    Trizen,
+    /// Any other program that is compatible with pacman's flags
+    Other(String),
+}
+
+impl AurHelper {
+    /// The helper set in the configuration, or otherwise the first one that
+    /// is installed.
+    pub fn detect(config: &Config) -> Option<AurHelper> {
+        let name = match &config.aur_helper {
+            Some(name) => name.as_str(),
+            None => KNOWN.into_iter().find(|name| command_exists(name))?,
+        };
+
+        Some(match name {
+            "yay" => AurHelper::Yay,
+            "paru" => AurHelper::Paru,
+            "pikaur" => AurHelper::Pikaur,
+            "trizen" => AurHelper::Trizen,
+            other => AurHelper::Other(other.to_string()),
+        })
+    }
+
+    pub fn program(&self) -> &str {
+        match self {
+            AurHelper::Yay => "yay",
+            AurHelper::Paru => "paru",
+            AurHelper::Pikaur => "pikaur",
+            AurHelper::Trizen => "trizen",
+            AurHelper::Other(program) => program,
+        }
+    }
+
+    pub fn command(&self) -> Command {
+        Command::new(self.program())
+    }
+
+    /// Arguments to list the outdated AUR packages, including the development
+    /// ones. yay can only do so when upgrading, so the prompt has to be
+    /// cancelled by closing its standard input.
+    pub fn dev_updates_args(&self) -> &'static [&'static str] {
+        match self {
+            AurHelper::Yay => &["-Sua", "--confirm", "--devel"],
+            AurHelper::Trizen => &["-Qu", "--aur", "--devel"],
+            _ => &["-Qua", "--devel"],
+        }
+    }
+
+    /// Whether a line listed with [`AurHelper::dev_updates_args`] is about a
+    /// development package. yay prefixes them with `devel/`, and the rest are
+    /// recognized by their name.
+    pub fn is_dev_update(&self, line: &str) -> bool {
+        match self {
+            AurHelper::Yay => line.contains("devel/"),
+            _ => line.split_whitespace().next().is_some_and(|pkg| {
+                let pkg = pkg.rsplit('/').next().unwrap_or(pkg);
+                VCS_SUFFIXES.iter().any(|suffix| pkg.ends_with(suffix))
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn yay_dev_updates() {
+        let yay = AurHelper::Yay;
+        assert!(yay.is_dev_update("devel/neovim-git latest-commit"));
+        assert!(!yay.is_dev_update("aur/spotify 1.2.0-1 -> 1.2.1-1"));
+    }
+
+    #[test]
+    fn dev_updates_by_name() {
+        let paru = AurHelper::Paru;
+        assert!(paru.is_dev_update("neovim-git 0.10.0.r1-1 -> 0.10.0.r2-1"));
+        assert!(paru.is_dev_update("aur/foo-svn r10-1 -> r12-1"));
+        assert!(!paru.is_dev_update("spotify 1.2.0-1 -> 1.2.1-1"));
+        assert!(!paru.is_dev_update("git-extras 7.0-1 -> 7.1-1"));
+        assert!(!paru.is_dev_update(""));
+    }
+}
@@ -0,0 +1,100 @@
+//! The results of the slow checks, like the disk usage, are cached in
+//! `~/.cache/arch-clean`, so that casual runs stay fast. They're reused for
+//! `cache-ttl` hours, unless `--refresh` is given, and removed once their fix
+//! is applied. What the fix needs from the check is cached along with them.
+
+use crate::{
+    cmd::{CleanupCommand, Output},
+    history, t,
+};
+
+use std::{env, fs, io, path::PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, warn};
+
+#[derive(Serialize, Deserialize)]
+struct Entry {
+    /// Seconds since the Unix epoch
+    time: u64,
+    /// See [`CleanupCommand::cache_key`]
+    key: String,
+    title: String,
+    content: String,
+    fix_available: bool,
+    reclaimable_bytes: Option<u64>,
+    /// See [`CleanupCommand::state`]
+    state: serde_json::Value,
+}
+
+fn path(id: &str) -> PathBuf {
+    let cache_dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap()).join(".cache"));
+    cache_dir.join("arch-clean").join(format!("{id}.json"))
+}
+
+/// The cached result of the check, if it's younger than `ttl` hours and for
+/// the same key, restoring its state into the command. Its title says how old
+/// it is.
+pub(crate) fn load(id: &str, key: &str, ttl: u64, cmd: &mut dyn CleanupCommand) -> Option<Output> {
+    let content = fs::read_to_string(path(id)).ok()?;
+    let entry = match serde_json::from_str::<Entry>(&content) {
+        Ok(entry) => entry,
+        Err(e) => {
+            warn!(error = %e, "invalid cached result");
+            return None;
+        }
+    };
+    if entry.key != key || history::now().saturating_sub(entry.time) >= ttl * 3600 {
+        debug!("cached result is outdated");
+        return None;
+    }
+    if let Err(e) = cmd.restore(entry.state) {
+        warn!(error = %e, "couldn't restore the cached result");
+        return None;
+    }
+
+    Some(Output {
+        title: t!(
+            "{title} (cached {age})",
+            title = entry.title,
+            age = history::ago(entry.time)
+        ),
+        content: entry.content,
+        fix_available: entry.fix_available,
+        reclaimable_bytes: entry.reclaimable_bytes,
+    })
+}
+
+/// Saves the result of the check along with the state of its command.
+pub(crate) fn save(id: &str, key: &str, out: &Output, cmd: &dyn CleanupCommand) -> Result<()> {
+    let entry = Entry {
+        time: history::now(),
+        key: key.to_string(),
+        title: out.title.clone(),
+        content: out.content.clone(),
+        fix_available: out.fix_available,
+        reclaimable_bytes: out.reclaimable_bytes,
+        state: cmd.state(),
+    };
+
+    let path = path(id);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("couldn't create {}", dir.display()))?;
+    }
+    fs::write(&path, serde_json::to_string(&entry)?)
+        .with_context(|| format!("couldn't write {}", path.display()))
+}
+
+/// Removes the cached result of the check, e.g., because its fix changed it.
+pub(crate) fn remove(id: &str) -> Result<()> {
+    let path = path(id);
+    match fs::remove_file(&path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("couldn't remove {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
@@ -1,9 +1,33 @@
-use crate::Config;
+use crate::{
+    aur::AurHelper,
+    color,
+    config::{Config, ConfirmPolicy, CustomCheck},
+    pkg, privilege,
+    process::TracedCommand,
+    t,
+    undo::{self, Change},
+};
 
-use std::{collections::HashSet, convert::TryInto, env, fmt, path::PathBuf, process::Stdio};
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    env,
+    ffi::{OsStr, OsString},
+    fmt,
+    io::{self, Write},
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    process::{ExitStatus, Stdio},
+    str::FromStr,
+    sync::OnceLock,
+    time::{Duration, SystemTime},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use async_trait::async_trait;
+use regex::Regex;
+use serde::{Deserialize, Serialize, Serializer};
 use tokio::{
     fs::{self, File},
     io::{AsyncBufReadExt, BufReader},
@@ -15,24 +39,726 @@ use tokio_stream::{
 };
 
 const PACMAN_LOG: &str = "/var/log/pacman.log";
+const PACMAN_LOCAL_DB: &str = "/var/lib/pacman/local";
+const PACMAN_CACHE: &str = "/var/cache/pacman/pkg";
+const MAN_CACHE: &str = "/var/cache/man";
+const KERNEL_MODULES: &str = "/usr/lib/modules";
+const DKMS_TREE: &str = "/var/lib/dkms";
+/// Maps configuration and data directory names to the packages that create
+/// them, for those that can't be guessed from the package's files.
+const APP_DIR_OWNERS: [(&str, &str); 14] = [
+    ("Code", "code"),
+    ("Code", "visual-studio-code-bin"),
+    ("VSCodium", "vscodium-bin"),
+    ("google-chrome", "google-chrome"),
+    ("BraveSoftware", "brave-bin"),
+    ("discord", "discord"),
+    ("Slack", "slack-desktop"),
+    ("spotify", "spotify"),
+    ("Signal", "signal-desktop"),
+    ("TelegramDesktop", "telegram-desktop"),
+    ("obs-studio", "obs-studio"),
+    ("libreoffice", "libreoffice-fresh"),
+    ("libreoffice", "libreoffice-still"),
+    ("Steam", "steam"),
+];
+/// Directories that are shared by many applications or created by the
+/// desktop itself rather than by a specific package.
+const APP_DIR_IGNORED: [&str; 19] = [
+    "applications",
+    "arch-clean",
+    "autostart",
+    "backgrounds",
+    "dconf",
+    "desktop-directories",
+    "fonts",
+    "gnome-shell",
+    "gtk-2.0",
+    "gtk-3.0",
+    "gtk-4.0",
+    "icons",
+    "keyrings",
+    "menus",
+    "mime",
+    "recently-used.xbel",
+    "sounds",
+    "systemd",
+    "Trash",
+];
+/// Subdirectories of `~/.zoom` that can be removed without losing settings
+const ZOOM_DISPOSABLE: [&str; 4] = ["logs", "reports", "download", "data/ConfAvatar"];
+const UPDATEDB_CONF: &str = "/etc/updatedb.conf";
+const CUPS_SPOOL: &str = "/var/spool/cups";
+const CUPS_PPD_DIRS: [&str; 2] = ["/etc/cups/ppd", "/var/cache/cups"];
+/// PPD files are usually a few dozen kilobytes, anything bigger than this is
+/// worth reporting.
+const CUPS_PPD_MAX_SIZE: u64 = 1024 * 1024;
+const AUDIT_LOGS: &str = "/var/log/audit";
+const AUDITD_CONF: &str = "/etc/audit/auditd.conf";
+/// The `num_logs` of auditd when it isn't configured
+const AUDITD_NUM_LOGS: usize = 5;
+/// Logs left behind by crashing or failing programs
+const CRASH_LOGS: [&str; 8] = [
+    "npm-debug.log*",
+    "yarn-error.log",
+    "pnpm-debug.log*",
+    "lerna-debug.log*",
+    "hs_err_pid*.log",
+    "replay_pid*.log",
+    "vgcore.*",
+    "valgrind*.log",
+];
+/// Completion caches bigger than this are likely corrupted or outdated
+const COMPLETION_CACHE_MAX_SIZE: u64 = 1024 * 1024;
+const WAYDROID_DIR: &str = "/var/lib/waydroid";
+/// Possible mount points of the EFI system partition
+const ESP_PATHS: [&str; 3] = ["/boot", "/efi", "/boot/efi"];
+const PACMAN_HOOKS: &str = "/etc/pacman.d/hooks";
+const DOCKER_CONTAINERS: &str = "/var/lib/docker/containers";
+const DOCKER_DAEMON_CONF: &str = "/etc/docker/daemon.json";
+/// The preferences that make Thunderbird compact the folders without asking
+const THUNDERBIRD_PREFS: [(&str, &str); 3] = [
+    ("mail.prompt_purge_threshhold", "true"),
+    ("mail.purge_threshhold_mb", "20"),
+    ("mail.purge.ask", "false"),
+];
+const GLOBAL_NODE_MODULES: &str = "/usr/lib/node_modules";
+const LOCATE_DBS: [&str; 2] = ["/var/lib/plocate/plocate.db", "/var/lib/mlocate/mlocate.db"];
+
+/// Whether a line of the output says that there's nothing to report, which may
+/// have been written in English or in the current language, e.g., by another
+/// host or in a previous run.
+pub fn is_none(line: &str) -> bool {
+    line == "(none)" || line == t!("(none)")
+}
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Serialize)]
 pub struct Output {
     pub title: String,
+    #[serde(serialize_with = "serialize_lines")]
     pub content: String,
     pub fix_available: bool,
+    /// Estimation of the disk space that would be freed by the fix
+    pub reclaimable_bytes: Option<u64>,
 }
 
-impl fmt::Display for Output {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+/// The content is serialized as a list of lines, which is easier to work with
+/// in scripts.
+fn serialize_lines<S: Serializer>(content: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(content.trim().lines())
+}
+
+impl Output {
+    /// How important it is to apply the fix, according to the space it would
+    /// free. Fixes that don't estimate it are a warning, since there's
+    /// something to clean up anyway.
+    pub fn severity(&self, config: &Config) -> Severity {
+        const MIB: u64 = 1024 * 1024;
+
+        if !self.fix_available {
+            return Severity::Info;
+        }
+        match self.reclaimable_bytes {
+            None => Severity::Warn,
+            Some(bytes) if bytes > config.critical_size * MIB => Severity::Critical,
+            Some(bytes) if bytes > config.warn_size * MIB => Severity::Warn,
+            Some(_) => Severity::Info,
+        }
+    }
+
+    /// Stops flagging the fix if it would free less than `min_size` bytes, so
+    /// that a few kilobytes aren't worth a cleanup.
+    pub(crate) fn filter_min_size(&mut self, min_size: u64) {
+        if self.reclaimable_bytes.is_some_and(|bytes| bytes < min_size) {
+            self.fix_available = false;
+        }
+    }
+
+    /// Whether there's nothing to report, e.g. `(none)` or `0 files`. Outputs
+    /// with a fix available are never empty.
+    pub fn is_empty(&self) -> bool {
+        if self.fix_available {
+            return false;
+        }
+
+        let content = self.content.trim();
+        content.is_empty() || is_none(content) || content.split_whitespace().next() == Some("0")
+    }
+
+    /// The output with its title colored by its severity.
+    pub fn render(&self, severity: Severity) -> String {
         let fix = if self.fix_available {
-            " (fix available)"
+            t!(" (fix available)")
         } else {
-            ""
+            String::new()
         };
-        writeln!(f, "\x1b[36;1m{}{}:\x1b[0m", self.title, fix)?;
-        writeln!(f, "{}", self.content.trim())
+        let title = color::paint(severity.color(), format!("{}{fix}:", self.title));
+        format!("{title}\n{}\n", self.content.trim())
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(Severity::Info))
+    }
+}
+
+/// What a fix did, as far as it's known.
+#[derive(Default, Debug, Clone)]
+pub struct FixResult {
+    /// Disk space freed by the files it removed, including the ones moved to
+    /// the trash. It's unknown for most external commands.
+    pub freed_bytes: Option<u64>,
+    /// Files, directories or packages removed
+    pub removed: usize,
+    /// How the last external command it ran exited, if any
+    pub status: Option<ExitStatus>,
+    /// The items it couldn't clean up, each with its error, while it went on
+    /// with the rest
+    pub failures: Vec<String>,
+}
+
+impl FixResult {
+    /// The fix only ran an external command, which didn't say what it did.
+    pub fn status(status: ExitStatus) -> Self {
+        FixResult {
+            status: Some(status),
+            ..FixResult::default()
+        }
+    }
+
+    /// Counts a path removed with [`remove_path`].
+    fn add_removed(&mut self, bytes: u64) {
+        self.removed += 1;
+        self.freed_bytes = Some(self.freed_bytes.unwrap_or(0) + bytes);
+    }
+
+    /// Records an item that couldn't be cleaned up, see
+    /// [`FixResult::failures`].
+    fn add_failure(&mut self, error: String) {
+        self.failures.push(error);
+    }
+
+    /// Adds up what another fix did, e.g., for the total of a run. The status
+    /// of the last one that failed is kept.
+    pub fn add(&mut self, other: &FixResult) {
+        self.removed += other.removed;
+        self.failures.extend(other.failures.iter().cloned());
+        if let Some(bytes) = other.freed_bytes {
+            self.freed_bytes = Some(self.freed_bytes.unwrap_or(0) + bytes);
+        }
+        if other.status.is_some_and(|status| !status.success()) || self.status.is_none() {
+            self.status = other.status.or(self.status);
+        }
+    }
+}
+
+impl fmt::Display for FixResult {
+    /// E.g. `freed 4.2 GiB, 3 removed, 1 failed`, or nothing if it isn't known.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(bytes) = self.freed_bytes {
+            parts.push(t!("freed {size}", size = human_size(bytes)));
+        }
+        if self.removed > 0 {
+            parts.push(t!("{removed} removed", removed = self.removed));
+        }
+        if let Some(status) = self.status.filter(|status| !status.success()) {
+            parts.push(t!("the command exited with {status}", status = status));
+        }
+        if !self.failures.is_empty() {
+            parts.push(t!("{failed} failed", failed = self.failures.len()));
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    #[default]
+    Info,
+    Warn,
+    Critical,
+}
+
+impl Severity {
+    /// The ANSI color of the section's title
+    fn color(&self) -> &'static str {
+        match self {
+            Severity::Info => "36;1",
+            Severity::Warn => "33;1",
+            Severity::Critical => "31;1",
+        }
+    }
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warn => "warn",
+            Severity::Critical => "critical",
+        })
+    }
+}
+
+/// A size in bytes given with a prefix, e.g. `100M` or `1.5 GiB`, see
+/// [`parse_size`].
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Deserialize)]
+#[serde(try_from = "String")]
+pub struct Size(pub u64);
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_size(s)
+            .map(Size)
+            .ok_or_else(|| format!("invalid size '{s}', expected e.g. `100M` or `1.5 GiB`"))
+    }
+}
+
+impl TryFrom<String> for Size {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Severity::Info),
+            "warn" => Ok(Severity::Warn),
+            "critical" => Ok(Severity::Critical),
+            _ => Err(format!(
+                "unknown severity '{s}', expected `info`, `warn` or `critical`"
+            )),
+        }
+    }
+}
+
+/// The user's home directory, which most of the commands inspect. It's the
+/// invoking user's unless another one is being checked.
+fn home_dir(config: &Config) -> PathBuf {
+    match &config.home {
+        Some(home) => home.clone(),
+        None => PathBuf::from(env::var("HOME").unwrap()),
+    }
+}
+
+/// An environment variable that configures where a program keeps its files.
+/// It's ignored when checking another user, since it's the invoking user's.
+fn user_var(config: &Config, var: &str) -> Option<OsString> {
+    env::var_os(var).filter(|_| config.home.is_none())
+}
+
+/// A directory of the user given by an XDG variable, or its default in the
+/// home directory.
+fn xdg_dir(config: &Config, var: &str, default: &str) -> PathBuf {
+    user_var(config, var).map_or_else(|| home_dir(config).join(default), PathBuf::from)
+}
+
+/// The directories where projects are searched for
+fn project_roots(config: &Config) -> Vec<PathBuf> {
+    if config.project_roots.is_empty() {
+        vec![home_dir(config)]
+    } else {
+        config.project_roots.clone()
+    }
+}
+
+/// Asks the user a yes/no question. This is a blocking operation.
+pub fn confirm(question: &str) -> Result<bool> {
+    print!(
+        "{} ",
+        color::paint("33", format!("{question} {}:", t!("[y/N]")))
+    );
+    let mut answer = String::new();
+    io::stdout().flush()?;
+    io::stdin().read_line(&mut answer)?;
+
+    // The answer in English is always accepted too
+    let answer = answer.trim();
+    Ok(answer == "y" || answer == t!("y"))
+}
+
+/// Asks for confirmation about a single item of a fix, e.g., for the ones that
+/// are only guessed to be removable. It's only answered by itself when the
+/// command is configured with `confirm = "auto"`, and otherwise the item is
+/// left alone with `yes`, which is meant to run unattended. The question is
+/// still printed to keep a record.
+fn confirm_item(config: &Config, question: &str) -> Result<bool> {
+    if config.confirm == Some(ConfirmPolicy::Auto) {
+        println!("{question} {}", t!("yes"));
+        return Ok(true);
+    }
+    if config.yes && config.confirm != Some(ConfirmPolicy::AlwaysAsk) {
+        println!("{question} {}", t!("Skipped"));
+        return Ok(false);
+    }
+
+    confirm(question)
+}
+
+/// Finds the program invoked by a command line such as `env FOO=1
+/// "/usr/bin/foo" --bar`, either as a path or in `$PATH`.
+fn find_program(cmdline: &str) -> Option<PathBuf> {
+    let program = cmdline
+        .split_whitespace()
+        .map(|arg| arg.trim_matches(|c| c == '"' || c == '\''))
+        .find(|arg| *arg != "env" && !arg.contains('='))?;
+
+    if program.contains('/') {
+        let path = PathBuf::from(program);
+        return path.exists().then_some(path);
+    }
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(program))
+        .find(|path| path.is_file())
+}
+
+/// Whether the program invoked by a command line exists.
+pub fn command_exists(cmdline: &str) -> bool {
+    find_program(cmdline).is_some()
+}
+
+/// Paths of the entries in a directory, or none if it can't be read.
+async fn read_dir_paths(dir: impl AsRef<Path>) -> Vec<PathBuf> {
+    match fs::read_dir(dir).await {
+        Ok(entries) => {
+            ReadDirStream::new(entries)
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect()
+                .await
+        }
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Removes a file or a directory with all its contents, so that it can be
+/// restored with `arch-clean undo` where possible. Returns the disk space it
+/// took.
+async fn remove_path(path: impl AsRef<Path>) -> io::Result<u64> {
+    undo::remove(path.as_ref()).await
+}
+
+/// Finds the files matching any of the name patterns under the given
+/// directories with `find`, skipping unreadable ones.
+async fn find_files<P: AsRef<OsStr>>(
+    dirs: impl IntoIterator<Item = P>,
+    names: &[&str],
+) -> Result<Vec<PathBuf>> {
+    let dirs = dirs.into_iter().collect::<Vec<_>>();
+    if dirs.is_empty() || names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Results in `( -name a -o -name b ... )`
+    let mut cmd = Command::new("find");
+    cmd.args(&dirs).arg("-type").arg("f").arg("(");
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            cmd.arg("-o");
+        }
+        cmd.arg("-name").arg(name);
+    }
+    let cmd = cmd.arg(")").stderr(Stdio::null()).traced_output().await?;
+    let stdout = String::from_utf8(cmd.stdout)?;
+
+    Ok(stdout.lines().map(PathBuf::from).collect())
+}
+
+/// Finds the directories with any of the given names under the given roots
+/// with `find`, without descending into them, into hidden directories that
+/// don't match, or into dependencies in `node_modules`.
+async fn find_dirs<P: AsRef<OsStr>>(
+    roots: impl IntoIterator<Item = P>,
+    names: &[&str],
+) -> Result<Vec<PathBuf>> {
+    let roots = roots.into_iter().collect::<Vec<_>>();
+    if roots.is_empty() || names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Results in `-type d ( -name a -o -name b ... ) -print -prune -o (
+    // -name .* -o -name node_modules ) -prune`
+    let mut cmd = Command::new("find");
+    cmd.args(&roots).arg("-type").arg("d").arg("(");
+    for (i, name) in names.iter().enumerate() {
+        if i > 0 {
+            cmd.arg("-o");
+        }
+        cmd.arg("-name").arg(name);
+    }
+    let cmd = cmd
+        .arg(")")
+        .arg("-print")
+        .arg("-prune")
+        .arg("-o")
+        .arg("(")
+        .arg("-name")
+        .arg(".*")
+        .arg("-o")
+        .arg("-name")
+        .arg("node_modules")
+        .arg(")")
+        .arg("-prune")
+        .stderr(Stdio::null())
+        .traced_output()
+        .await?;
+    let stdout = String::from_utf8(cmd.stdout)?;
+
+    Ok(stdout.lines().map(PathBuf::from).collect())
+}
+
+/// Reads the string values of a key in a JSON document without parsing it,
+/// e.g. `1091` in `{"revision": "1091"}`.
+fn json_strings<'a>(json: &'a str, key: &str) -> Vec<&'a str> {
+    json.split(&format!("\"{key}\""))
+        .skip(1)
+        .filter_map(|rest| rest.trim_start().strip_prefix(':'))
+        .filter_map(|rest| rest.trim_start().strip_prefix('"'))
+        .filter_map(|rest| rest.split('"').next())
+        .collect()
+}
+
+/// Reads an attribute from an XML tag such as `<folder path="/foo">`, quoted
+/// with either `"` or `'`, without unescaping its value. The tag may be given
+/// without its opening bracket.
+fn xml_attr<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    tag.match_indices(name).find_map(|(i, _)| {
+        // Not the end of another attribute, e.g. `folderpath` for `path`
+        if i != 0 && !tag[..i].ends_with(char::is_whitespace) {
+            return None;
+        }
+        let rest = tag[i + name.len()..].strip_prefix('=')?;
+        let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+        let value = &rest[1..];
+        Some(&value[..value.find(quote)?])
+    })
+}
+
+/// Reads the value of a key in an INI-like file such as `.desktop` entries or
+/// systemd units, ignoring the section it's in.
+fn ini_value<'a>(content: &'a str, key: &str) -> Option<&'a str> {
+    content.lines().find_map(|line| {
+        let (k, v) = line.split_once('=')?;
+        (k.trim() == key).then(|| v.trim())
+    })
+}
+
+/// Lowercase names that installed applications may use for the directories
+/// they create in the home, e.g. `~/.config/<name>`.
+async fn installed_app_names(config: &Config) -> Result<HashSet<String>> {
+    let backend = pkg::backend(config);
+    let pkgs = backend
+        .installed()
+        .await?
+        .iter()
+        .map(|pkg| pkg.to_lowercase())
+        .collect::<HashSet<_>>();
+
+    // The names of the binaries and the directories installed by the
+    // packages are usually the same as the ones they create in the home.
+    let files = backend.files().await?;
+    let mut names = files
+        .iter()
+        .filter_map(|file| {
+            ["/usr/bin/", "/usr/share/", "/usr/lib/", "/etc/", "/opt/"]
+                .iter()
+                .find_map(|prefix| file.strip_prefix(prefix))
+        })
+        .filter_map(|rest| rest.split('/').next())
+        .filter(|name| !name.is_empty())
+        .map(str::to_lowercase)
+        .collect::<HashSet<_>>();
+    names.extend(pkgs.iter().cloned());
+    names.extend(
+        APP_DIR_OWNERS
+            .iter()
+            .filter(|(_, pkg)| pkgs.contains(*pkg))
+            .map(|(dir, _)| dir.to_lowercase()),
+    );
+
+    Ok(names)
+}
+
+/// Total disk usage of the given paths in bytes, according to `du`. Missing
+/// or unreadable paths are ignored.
+async fn disk_usage<P: AsRef<OsStr>>(paths: impl IntoIterator<Item = P>) -> Result<u64> {
+    let paths = paths.into_iter().collect::<Vec<_>>();
+    // Without arguments `du` would measure the current directory
+    if paths.is_empty() {
+        return Ok(0);
+    }
+
+    let cmd = Command::new("du")
+        .arg("-sc")
+        .arg("--block-size=1")
+        .args(&paths)
+        .stderr(Stdio::null())
+        .traced_output()
+        .await?;
+    let stdout = String::from_utf8(cmd.stdout)?;
+    // The last line is the grand total
+    let total = stdout
+        .lines()
+        .last()
+        .and_then(|line| line.split_whitespace().next())
+        .and_then(|bytes| bytes.parse().ok())
+        .unwrap_or(0);
+
+    Ok(total)
+}
+
+static BYTE_FORMAT: OnceLock<ByteFormat> = OnceLock::new();
+
+/// How the sizes are shown, see [`human_size`].
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ByteFormat {
+    /// In bytes, e.g. `1572864 B`
+    Raw,
+    /// With binary prefixes, e.g. `1.5 MiB`
+    #[default]
+    Iec,
+    /// With decimal prefixes, e.g. `1.6 MB`
+    Si,
+}
+
+impl fmt::Display for ByteFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ByteFormat::Raw => "raw",
+            ByteFormat::Iec => "iec",
+            ByteFormat::Si => "si",
+        })
+    }
+}
+
+impl FromStr for ByteFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "raw" => Ok(ByteFormat::Raw),
+            "iec" => Ok(ByteFormat::Iec),
+            "si" => Ok(ByteFormat::Si),
+            _ => Err(format!(
+                "unknown byte format '{s}', expected `raw`, `iec` or `si`"
+            )),
+        }
+    }
+}
+
+/// Sets how the sizes are shown for the rest of the program.
+pub fn init_byte_format(format: ByteFormat) {
+    let _ = BYTE_FORMAT.set(format);
+}
+
+/// See [`init_byte_format`], with binary prefixes if it wasn't set.
+pub fn byte_format() -> ByteFormat {
+    BYTE_FORMAT.get().copied().unwrap_or_default()
+}
+
+/// Formats a size in bytes as set with [`init_byte_format`], e.g. `1.5 GiB`.
+pub fn human_size(bytes: u64) -> String {
+    format_size(bytes, byte_format())
+}
+
+/// See [`human_size`].
+fn format_size(bytes: u64, format: ByteFormat) -> String {
+    let (base, units) = match format {
+        ByteFormat::Raw => return format!("{bytes} B"),
+        ByteFormat::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+        ByteFormat::Si => (1000.0, ["B", "kB", "MB", "GB", "TB"]),
+    };
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= base && unit < units.len() - 1 {
+        size /= base;
+        unit += 1;
     }
+
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", units[unit])
+    }
+}
+
+/// Parses a size such as the ones printed by pacman, e.g. `120.50 MiB`, or by
+/// `du -h`, e.g. `1.5G`, whose single-letter prefixes are binary too. Decimal
+/// prefixes are supported as well, e.g. `3.8 GB` or `12kB`, and sizes without
+/// a unit are in bytes.
+fn parse_size(size: &str) -> Option<u64> {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    const SHORT_UNITS: [&str; 5] = ["B", "K", "M", "G", "T"];
+    const SI_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let size = size.trim();
+    let (value, unit) = size.split_at(size.find(char::is_alphabetic).unwrap_or(size.len()));
+    let value: f64 = value.trim().parse().ok()?;
+    let binary = UNITS.iter().position(|u| *u == unit).or_else(|| {
+        SHORT_UNITS
+            .iter()
+            .position(|u| unit.is_empty() || u.eq_ignore_ascii_case(unit))
+    });
+    let bytes = if let Some(exp) = binary {
+        value * 1024f64.powi(exp as i32)
+    } else {
+        let exp = SI_UNITS.iter().position(|u| u.eq_ignore_ascii_case(unit))?;
+        value * 1000f64.powi(exp as i32)
+    };
+    Some(bytes as u64)
+}
+
+/// Last modification time of a path, if it exists.
+async fn modified(path: impl AsRef<Path>) -> Option<SystemTime> {
+    fs::metadata(path).await.ok()?.modified().ok()
+}
+
+/// Last access time of a path, if it exists. Note that with the default
+/// `relatime` mount option this is only updated once a day.
+async fn accessed(path: impl AsRef<Path>) -> Option<SystemTime> {
+    fs::metadata(path).await.ok()?.accessed().ok()
+}
+
+/// Whether the access times of the files in a path are updated, i.e., it
+/// isn't mounted with `noatime`, according to `/proc/self/mounts`.
+async fn atime_updated(path: &Path) -> bool {
+    let path = fs::canonicalize(path)
+        .await
+        .unwrap_or_else(|_| path.to_path_buf());
+    let mounts = fs::read_to_string("/proc/self/mounts")
+        .await
+        .unwrap_or_default();
+    // The closest mount point is the one with the longest path, and the
+    // spaces in them are escaped as `\040`
+    let options = mounts
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let mount_point = PathBuf::from(fields.get(1)?.replace("\\040", " "));
+            let options = *fields.get(3)?;
+            path.starts_with(&mount_point)
+                .then(|| (mount_point.components().count(), options))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, options)| options);
+
+    options.is_none_or(|options| !options.split(',').any(|option| option == "noatime"))
+}
+
+/// Number of whole days elapsed since the given time.
+fn days_since(time: SystemTime) -> u64 {
+    time.elapsed().map(|d| d.as_secs() / 86400).unwrap_or(0)
 }
 
 #[async_trait]
@@ -40,11 +766,31 @@ pub trait CleanupCommand: Sync + Send {
     /// Runs the command and checks the output.
     async fn check(&mut self, config: &Config) -> Result<Output>;
 
-    /// Non-blocking, this will just show the user what `apply_fix` does.
-    fn show_fix(&self, config: &Config);
+    /// Non-blocking, this will just show the user what `apply_fix` does,
+    /// writing it to `out`.
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()>;
 
     /// Applies the suggested fix for the command.
-    async fn apply_fix(&self, config: &Config) -> Result<()>;
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult>;
+
+    /// The results of the slow checks are cached, see [`crate::cache`]. This
+    /// is what the result depends on apart from the system, e.g., the
+    /// options it uses, so that it's only reused for the same ones. It's
+    /// `None` for the commands that aren't cached.
+    fn cache_key(&self, _config: &Config) -> Option<String> {
+        None
+    }
+
+    /// What the fix needs from the check, saved along its cached result.
+    fn state(&self) -> serde_json::Value {
+        serde_json::Value::Null
+    }
+
+    /// Restores what [`CleanupCommand::state`] returned instead of running
+    /// the check.
+    fn restore(&mut self, _state: serde_json::Value) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Default)]
@@ -62,9 +808,7 @@ impl CleanupCommand for LastInstalled {
         }
 
         // First obtaining all installed packages
-        let cmd = Command::new("pacman").arg("-Qqe").output().await?;
-        let stdout = String::from_utf8(cmd.stdout)?;
-        let installed = stdout.lines().collect::<HashSet<_>>();
+        let installed = pkg::backend(config).explicit().await?;
 
         // To find unique package entries
         let mut unique = HashSet::new();
@@ -89,7 +833,7 @@ impl CleanupCommand for LastInstalled {
                 })
             })
             .filter(|e| e.action == "installed") // Only installations
-            .filter(|e| installed.contains(e.pkg.as_str())) // Only still installed packages
+            .filter(|e| installed.contains(&e.pkg)) // Only still installed packages
             .filter(|e| unique.insert(e.pkg.clone())) // Unique
             .map(|e| format!("{} {} {}", e.time, e.pkg, e.version))
             .take(config.max_packages)
@@ -97,17 +841,21 @@ impl CleanupCommand for LastInstalled {
             .join("\n");
 
         Ok(Output {
-            title: format!("Last {} explicitly installed packages", config.max_packages),
+            title: t!(
+                "Last {count} explicitly installed packages",
+                count = config.max_packages
+            ),
             content,
             fix_available: false,
+            reclaimable_bytes: None,
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
+    fn show_fix(&self, _config: &Config, _out: &mut dyn Write) -> io::Result<()> {
         unimplemented!()
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
         unimplemented!()
     }
 }
@@ -116,73 +864,164 @@ impl CleanupCommand for LastInstalled {
 pub struct OrphanPackages {
     pkgs: Vec<String>,
 }
+
 #[async_trait]
 impl CleanupCommand for OrphanPackages {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
-        let cmd = Command::new("pacman").arg("-Qqtd").output().await?;
-        let mut content = String::from_utf8(cmd.stdout)?;
-        self.pkgs = content.lines().map(ToString::to_string).collect();
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        self.pkgs = pkg::backend(config).orphans().await?;
+        let mut content = self.pkgs.join("\n");
         // Default message instead of empty string
         if content.is_empty() {
-            content.push_str("(none)");
+            content.push_str(&t!("(none)"));
         }
 
         Ok(Output {
-            title: "Orphan packages".to_string(),
+            title: t!("Orphan packages"),
             content,
             fix_available: !self.pkgs.is_empty(),
+            reclaimable_bytes: None,
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        let pkgs = self.pkgs.join(" ");
-        println!("This fix will run the command:");
-        println!("  yay -Rns --noconfirm {pkgs}");
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let command = pkg::backend(config).remove_command(config, &self.pkgs);
+        writeln!(out, "{}", t!("This fix will run the command:"))?;
+        writeln!(out, "  {command}")?;
+
+        Ok(())
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
-        Command::new("yay")
-            .arg("-Rns")
-            .arg("--noconfirm")
-            .args(&self.pkgs)
-            .output()
-            .await?;
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        // `-s` also removes the dependencies that aren't needed anymore, so
+        // what's recorded for undo is everything that's gone afterwards
+        let backend = pkg::backend(config);
+        let before = backend.installed().await?;
+        backend.remove(config, &self.pkgs).await?;
 
-        Ok(())
+        let after = backend
+            .installed()
+            .await?
+            .into_iter()
+            .collect::<HashSet<_>>();
+        let pkgs = before
+            .into_iter()
+            .filter(|pkg| !after.contains(pkg))
+            .collect::<Vec<_>>();
+        let fixed = FixResult {
+            removed: pkgs.len(),
+            ..FixResult::default()
+        };
+        if !pkgs.is_empty() {
+            undo::record(Change::RemovedPackages { pkgs });
+        }
+
+        Ok(fixed)
     }
 }
 
 #[derive(Default)]
 pub struct Paccache;
+impl Paccache {
+    /// The arguments of each run of `paccache`: one that keeps the last `keep`
+    /// versions of every package, and then, with `purge-uninstalled`, one that
+    /// removes all the versions of the uninstalled ones.
+    fn runs(config: &Config) -> Vec<Vec<String>> {
+        let mut runs = vec![vec![format!("-k{}", config.keep)]];
+        if config.purge_uninstalled {
+            runs.push(vec!["-u".to_string(), "-k0".to_string()]);
+        }
+        runs
+    }
+
+    /// The packages listed by a verbose dry run of `paccache`, relative to the
+    /// cache unless they're absolute.
+    fn candidates(stdout: &str) -> impl Iterator<Item = PathBuf> + '_ {
+        stdout
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with("==>"))
+            .map(|line| Path::new(PACMAN_CACHE).join(line))
+    }
+}
 #[async_trait]
 impl CleanupCommand for Paccache {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
-        let cmd = Command::new("paccache")
-            .arg("-d")
-            .arg("-v")
-            .arg("--nocolor")
-            .output()
-            .await?;
-        let content = String::from_utf8(cmd.stdout)?;
-        let fix_available = content.lines().count() != 1;
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let mut content = Vec::new();
+        let mut fix_available = false;
+        // The old versions of the uninstalled packages are candidates of both
+        // runs, so they're counted once.
+        let mut candidates = HashSet::new();
+        for args in Paccache::runs(config) {
+            let cmd = Command::new("paccache")
+                .arg("-d")
+                .arg("-v")
+                .arg("--nocolor")
+                .args(&args)
+                .traced_output()
+                .await?;
+            let stdout = String::from_utf8(cmd.stdout)?;
+            fix_available |= stdout.lines().count() != 1;
+            candidates.extend(Paccache::candidates(&stdout));
+            content.push(stdout);
+        }
+
+        let mut reclaimable_bytes = None;
+        for path in &candidates {
+            if let Ok(meta) = fs::metadata(path).await {
+                *reclaimable_bytes.get_or_insert(0) += meta.len();
+            }
+        }
 
         Ok(Output {
-            title: "Cache cleaning".to_string(),
-            content,
+            title: t!("Cache cleaning"),
+            content: content.join("\n"),
             fix_available,
+            reclaimable_bytes,
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        println!("This fix will run the command 'paccache -r'");
-    }
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let commands = Paccache::runs(config)
+            .iter()
+            .map(|args| format!("paccache -r {}", args.join(" ")))
+            .collect::<Vec<_>>();
+        if let [command] = &commands[..] {
+            writeln!(
+                out,
+                "{}",
+                t!(
+                    "This fix will run the command '{command}'",
+                    command = command
+                )
+            )?;
+            return Ok(());
+        }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
-        let mut cmd = Command::new("paccache").arg("-r").spawn()?;
-        cmd.wait().await?;
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        for command in commands {
+            writeln!(out, "  {command}")?;
+        }
 
         Ok(())
     }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for args in Paccache::runs(config) {
+            let status = Command::new("paccache")
+                .arg("-r")
+                .args(&args)
+                .traced_status()
+                .await?;
+            fixed = FixResult::status(status);
+            // The uninstalled packages are left alone if the first run failed
+            if !status.success() {
+                break;
+            }
+        }
+
+        Ok(fixed)
+    }
 }
 
 /// TODO: yay cache
@@ -196,107 +1035,152 @@ impl CleanupCommand for Paccache {
 pub struct TrashSize;
 #[async_trait]
 impl CleanupCommand for TrashSize {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
-        let cmd = Command::new("du")
-            .arg("-hs")
-            .arg(env::var("HOME").unwrap() + "/.local/share/Trash")
-            .output()
-            .await?;
-        let content = String::from_utf8(cmd.stdout)?;
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let trash = home_dir(config).join(".local/share/Trash");
         // The trash can be emptied only when the size shown by du is other than
         // zero.
-        let empty_trash = matches!(content.split_whitespace().next(), Some("0"));
+        let size = disk_usage([&trash]).await?;
 
         Ok(Output {
-            title: "Trash size".to_string(),
-            content,
-            fix_available: !empty_trash,
+            title: t!("Trash size"),
+            content: format!("{}\t{}", human_size(size), trash.display()),
+            fix_available: size != 0,
+            reclaimable_bytes: Some(size),
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        println!("This fix will run the command 'trash-empty'");
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        let command = format!("{sudo}trash-empty");
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will run the command '{command}'",
+                command = command
+            )
+        )?;
+
+        Ok(())
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
-        let mut cmd = Command::new("sudo").arg("trash-empty").spawn()?;
-        cmd.wait().await?;
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut cmd = privilege::command(config, "trash-empty").await?;
+        // The trash of the user being checked
+        if let Some(home) = &config.home {
+            cmd.env("HOME", home).env_remove("XDG_DATA_HOME");
+        }
+        let status = cmd.traced_status().await?;
 
-        Ok(())
+        Ok(FixResult::status(status))
     }
 }
 
 #[derive(Default)]
-pub struct DevUpdates;
+pub struct DevUpdates {
+    helper: Option<AurHelper>,
+}
 #[async_trait]
 impl CleanupCommand for DevUpdates {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
-        let cmd = Command::new("yay")
-            .arg("-Sua")
-            .arg("--confirm")
-            .arg("--devel")
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let helper = match AurHelper::detect(config) {
+            Some(helper) => self.helper.insert(helper),
+            None => bail!("no AUR helper found, it can be set with `aur-helper`"),
+        };
+        let cmd = helper
+            .command()
+            .args(helper.dev_updates_args())
             .stdin(Stdio::null()) // EOF for "dry run"
-            .output()
+            .traced_output()
             .await?;
         let stdout = String::from_utf8(cmd.stdout)?;
         let mut content = stdout
             .lines()
-            .filter(|line| line.to_string().contains("devel/"))
+            .filter(|line| helper.is_dev_update(line))
             .collect::<Vec<_>>()
             .join("\n");
         let fix_available = content.lines().count() > 0;
         // Default message instead of empty string
         if content.is_empty() {
-            content.push_str("(none)");
+            content.push_str(&t!("(none)"));
         }
 
         Ok(Output {
-            title: "Developer updates".to_string(),
+            title: t!("Developer updates"),
             content,
             fix_available,
+            reclaimable_bytes: None,
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        println!("This fix will run the command 'yay -Syu --devel'");
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        if let Some(helper) = &self.helper {
+            let command = format!("{} -Syu --devel", helper.program());
+            writeln!(
+                out,
+                "{}",
+                t!(
+                    "This fix will run the command '{command}'",
+                    command = command
+                )
+            )?;
+        }
+
+        Ok(())
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
-        let mut cmd = Command::new("yay").arg("-Syu").arg("--devel").spawn()?;
-        cmd.wait().await?;
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let helper = self.helper.as_ref().context("no AUR helper found")?;
+        let status = helper
+            .command()
+            .arg("-Syu")
+            .arg("--devel")
+            .traced_status()
+            .await?;
 
-        Ok(())
+        Ok(FixResult::status(status))
     }
 }
 
 #[derive(Default)]
 pub struct NeovimSwapFiles {
-    swap_dir: String,
+    swap_dir: PathBuf,
 }
 #[async_trait]
 impl CleanupCommand for NeovimSwapFiles {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
-        self.swap_dir = env::var("HOME").unwrap() + "/.local/share/nvim/swap";
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        self.swap_dir = home_dir(config).join(".local/share/nvim/swap");
         let count = match fs::read_dir(&self.swap_dir).await {
             Err(_) => 0,
             Ok(dir) => ReadDirStream::new(dir).fold(0, |acc, _| acc + 1).await, // No `.count` available yet
         };
 
         Ok(Output {
-            title: "NeoVim swap files".to_owned(),
+            title: t!("NeoVim swap files"),
             content: format!("{count} files"),
             fix_available: count > 0,
+            reclaimable_bytes: Some(disk_usage([&self.swap_dir]).await?),
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        println!("This fix will remove the directory '{}'", self.swap_dir);
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the directory '{dir}'",
+                dir = self.swap_dir.display()
+            )
+        )?;
+
+        Ok(())
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
-        fs::remove_dir_all(&self.swap_dir).await?;
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        fixed.add_removed(remove_path(&self.swap_dir).await?);
 
-        Ok(())
+        Ok(fixed)
     }
 }
 
@@ -306,7 +1190,7 @@ pub struct DiskUsage;
 impl CleanupCommand for DiskUsage {
     async fn check(&mut self, config: &Config) -> Result<Output> {
         // Will only show the sizes of the nodes in the user's home.
-        let home = PathBuf::from(env::var("HOME").unwrap());
+        let home = home_dir(config);
         let nodes = ReadDirStream::new(fs::read_dir(&home).await?)
             .map(|node| {
                 node.map(|dir| dir.file_name())
@@ -316,7 +1200,8 @@ impl CleanupCommand for DiskUsage {
             .await?;
 
         let mut cmd = Command::new("du")
-            .arg("-sch")
+            .arg("-sc")
+            .arg("--block-size=1")
             .args(&nodes)
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
@@ -324,30 +1209,44 @@ impl CleanupCommand for DiskUsage {
         cmd.wait().await?;
         let du_stdin: Stdio = cmd.stdout.take().unwrap().try_into().unwrap();
         let cmd = Command::new("sort")
-            .arg("-rh")
+            .arg("-rn")
             .stdin(du_stdin)
-            .output()
+            .traced_output()
             .await?;
         let out = String::from_utf8(cmd.stdout)?;
-        let out = out.lines().take(config.max_disk_usage).collect::<Vec<_>>();
+        let out = out
+            .lines()
+            .filter_map(|line| {
+                let (size, node) = line.split_once('\t')?;
+                Some((size.parse::<u64>().ok()?, node))
+            })
+            .filter(|(size, _)| *size >= config.min_size)
+            .take(config.max_disk_usage)
+            .map(|(size, node)| format!("{}\t{node}", human_size(size)))
+            .collect::<Vec<_>>();
 
         Ok(Output {
-            title: format!(
-                "Disk usage of top {} largest nodes in home directory",
-                out.len()
+            title: t!(
+                "Disk usage of top {count} largest nodes in home directory",
+                count = out.len()
             ),
             content: out.join("\n"),
             fix_available: false,
+            reclaimable_bytes: None,
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
+    fn show_fix(&self, _config: &Config, _out: &mut dyn Write) -> io::Result<()> {
         unimplemented!()
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
         unimplemented!()
     }
+
+    fn cache_key(&self, config: &Config) -> Option<String> {
+        Some(format!("{}:{}", config.max_disk_usage, config.min_size))
+    }
 }
 
 #[derive(Default)]
@@ -356,10 +1255,10 @@ pub struct RustTarget {
 }
 #[async_trait]
 impl CleanupCommand for RustTarget {
-    async fn check(&mut self, _config: &Config) -> Result<Output> {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
         // First finding all Rust projects
         let cmd = Command::new("find")
-            .arg(env::var("HOME").unwrap())
+            .arg(home_dir(config))
             .arg("-name")
             .arg("Cargo.toml")
             .arg("-type")
@@ -373,7 +1272,7 @@ impl CleanupCommand for RustTarget {
             .arg(";")
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
-            .output()
+            .traced_output()
             .await?;
         let dirs = String::from_utf8(cmd.stdout)?;
 
@@ -393,7 +1292,7 @@ impl CleanupCommand for RustTarget {
                 .arg(";")
                 .stdout(Stdio::piped())
                 .stderr(Stdio::null())
-                .output()
+                .traced_output()
                 .await?;
 
             // Sum the kilobytes of each directory
@@ -404,7 +1303,7 @@ impl CleanupCommand for RustTarget {
                     Some((kb, path)) => (kb.parse().unwrap_or(0), PathBuf::from(path)),
                     None => panic!("unexpected output from `du`: {line}"),
                 })
-                .filter(|(ref kb, _)| kb > &0)
+                .filter(|(ref kb, _)| kb > &0 && *kb as u64 * 1024 >= config.min_size)
                 .collect::<Vec<_>>();
 
             // If it's not empty, insert the directories into the list and add
@@ -419,34 +1318,5114 @@ impl CleanupCommand for RustTarget {
         }
 
         // Some users also configure a global compilation directory
-        if let Ok(global_dir) = env::var("CARGO_TARGET_DIR") {
+        if let Some(global_dir) = user_var(config, "CARGO_TARGET_DIR") {
             let path = PathBuf::from(global_dir);
-            if path.exists() {
+            if path.exists() && disk_usage([&path]).await? >= config.min_size {
                 self.dirs.insert(path);
             }
         }
 
         Ok(Output {
-            title: "Size of Rust target directories".to_string(),
-            content: format!("{} MB", total_kb / 1024),
+            title: t!("Size of Rust target directories"),
+            content: human_size(total_kb as u64 * 1024),
             fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.dirs).await?),
         })
     }
 
-    fn show_fix(&self, _config: &Config) {
-        println!("This fix will remove the following directories:");
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
         for dir in &self.dirs {
-            println!("* {}", dir.to_str().unwrap());
+            writeln!(out, "* {}", dir.to_str().unwrap())?;
         }
+
+        Ok(())
     }
 
-    async fn apply_fix(&self, _config: &Config) -> Result<()> {
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
         for dir in &self.dirs {
-            if let Err(e) = fs::remove_dir_all(dir).await {
-                eprintln!("Failed to remove {dir:?}: {e}");
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
             }
         }
 
-        Ok(())
+        Ok(fixed)
+    }
+
+    fn cache_key(&self, config: &Config) -> Option<String> {
+        let global_dir = user_var(config, "CARGO_TARGET_DIR").unwrap_or_default();
+        Some(format!(
+            "{}:{}",
+            global_dir.to_string_lossy(),
+            config.min_size
+        ))
+    }
+
+    fn state(&self) -> serde_json::Value {
+        serde_json::json!(self.dirs)
+    }
+
+    fn restore(&mut self, state: serde_json::Value) -> Result<()> {
+        self.dirs = serde_json::from_value(state)?;
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+pub struct ManDbCache;
+#[async_trait]
+impl CleanupCommand for ManDbCache {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        if !command_exists("mandb") {
+            return Ok(Output {
+                title: t!("man-db cache"),
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        let size = disk_usage([MAN_CACHE]).await?;
+
+        // The index is considered stale when packages have been installed or
+        // removed since it was last regenerated, as it may still reference
+        // pages that no longer exist.
+        let index = modified(Path::new(MAN_CACHE).join("index.db")).await;
+        let pkgs = modified(PACMAN_LOCAL_DB).await;
+        let (status, stale) = match (index, pkgs) {
+            (None, _) => (t!("Index missing"), true),
+            (Some(index), Some(pkgs)) if index < pkgs => (
+                t!(
+                    "Index outdated, last built {days} days ago",
+                    days = days_since(index)
+                ),
+                true,
+            ),
+            (Some(index), _) => (
+                t!(
+                    "Index up to date, last built {days} days ago",
+                    days = days_since(index)
+                ),
+                false,
+            ),
+        };
+
+        Ok(Output {
+            title: t!("man-db cache"),
+            content: format!(
+                "{}\n{status}",
+                t!("{size} in {dir}", size = human_size(size), dir = MAN_CACHE)
+            ),
+            fix_available: stale,
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(out, "{}", t!("This fix will run the command '{command}', which regenerates the index and purges the entries of removed packages", command = format!("{sudo}mandb")))?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "mandb")
+            .await?
+            .arg("--quiet")
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct LocateDb;
+#[async_trait]
+impl CleanupCommand for LocateDb {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Both plocate and mlocate are supported, the first one found is used
+        let mut found = None;
+        for db in LOCATE_DBS {
+            if let Some(time) = modified(db).await {
+                found = Some((PathBuf::from(db), time));
+                break;
+            }
+        }
+        let (db, time) = match found {
+            Some(found) => found,
+            None => {
+                return Ok(Output {
+                    title: t!("Locate database"),
+                    content: t!("(not installed)"),
+                    fix_available: false,
+                    reclaimable_bytes: None,
+                })
+            }
+        };
+
+        let size = disk_usage([&db]).await?;
+        let age = days_since(time);
+        let mut content = t!(
+            "{size} in {dir}, last updated {days} days ago",
+            size = human_size(size),
+            dir = db.display(),
+            days = age
+        );
+        let mut fix_available = age > config.max_age;
+
+        // If the configuration excludes paths that the database still has
+        // entries for, it was built before they were excluded.
+        let conf = fs::read_to_string(UPDATEDB_CONF).await.unwrap_or_default();
+        let pruned = conf
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("PRUNEPATHS"))
+            .filter_map(|value| value.trim_start().strip_prefix('='))
+            .flat_map(|value| value.trim().trim_matches('"').split_whitespace())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        for path in pruned {
+            let cmd = Command::new("locate")
+                .arg("--limit")
+                .arg("1")
+                .arg("--regex")
+                .arg(format!("^{path}/"))
+                .stderr(Stdio::null())
+                .traced_output()
+                .await?;
+            if !cmd.stdout.is_empty() {
+                content.push('\n');
+                content.push_str(&t!("Indexes excluded path {path}", path = path));
+                fix_available = true;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Locate database"),
+            content,
+            fix_available,
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        let command = format!("{sudo}updatedb");
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will run the command '{command}'",
+                command = command
+            )
+        )?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "updatedb")
+            .await?
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct DkmsLeftovers {
+    /// Pairs of `module/version` and kernel release
+    stale: Vec<(String, String)>,
+}
+impl DkmsLeftovers {
+    /// The `module/version` and kernel release of a line of `dkms status`,
+    /// which look like `nvidia/545.29.06, 6.6.8-arch1-1, x86_64: installed`,
+    /// or `nvidia, 545.29.06, 6.6.8-arch1-1, x86_64: installed` in older
+    /// versions of DKMS.
+    fn parse_status(line: &str) -> Option<(String, &str)> {
+        let (info, _status) = line.split_once(':').unwrap_or((line, ""));
+        let fields = info.split(", ").collect::<Vec<_>>();
+        match fields[..] {
+            [module, kernel, ..] if module.contains('/') => Some((module.to_string(), kernel)),
+            [name, version, kernel, ..] => Some((format!("{name}/{version}"), kernel)),
+            _ => None,
+        }
+    }
+}
+#[async_trait]
+impl CleanupCommand for DkmsLeftovers {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let cmd = Command::new("dkms").arg("status").traced_output().await?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for line in stdout.lines() {
+            let Some((module, kernel)) = DkmsLeftovers::parse_status(line) else {
+                continue;
+            };
+
+            // A kernel is still installed if its image is available
+            let image = Path::new(KERNEL_MODULES).join(kernel).join("vmlinuz");
+            if fs::metadata(image).await.is_ok() {
+                continue;
+            }
+
+            let size = disk_usage([Path::new(DKMS_TREE).join(&module).join(kernel)]).await?;
+            content.push(format!("{module} for {kernel} ({})", human_size(size)));
+            reclaimable += size;
+            self.stale.push((module, kernel.to_string()));
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("DKMS modules for removed kernels"),
+            content: content.join("\n"),
+            fix_available: !self.stale.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        for (module, kernel) in &self.stale {
+            writeln!(out, "  {sudo}dkms remove {module} -k {kernel}")?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for (module, kernel) in &self.stale {
+            let status = privilege::command(config, "dkms")
+                .await?
+                .arg("remove")
+                .arg(module)
+                .arg("-k")
+                .arg(kernel)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult::status(status));
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct AppLeftovers {
+    dirs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for AppLeftovers {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let names = installed_app_names(config).await?;
+        let home = home_dir(config);
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for parent in [home.join(".config"), home.join(".local/share")] {
+            let entries = match fs::read_dir(&parent).await {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            let mut entries = ReadDirStream::new(entries);
+            while let Some(entry) = entries.next().await {
+                let entry = entry?;
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if !entry.file_type().await?.is_dir()
+                    || name.starts_with('.')
+                    || APP_DIR_IGNORED.contains(&name.as_str())
+                    || names.contains(&name.to_lowercase())
+                {
+                    continue;
+                }
+
+                let size = disk_usage([entry.path()]).await?;
+                content.push(format!("{} ({})", entry.path().display(), human_size(size)));
+                self.dirs.push(entry.path());
+                reclaimable += size;
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Leftovers of uninstalled applications"),
+            content: content.join("\n"),
+            fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will ask to remove each of the following directories:")
+        )?;
+        for dir in &self.dirs {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        // The mapping is based on heuristics, so each directory is confirmed
+        // individually.
+        for dir in &self.dirs {
+            if !confirm_item(config, &t!("Remove {path}?", path = dir.display()))? {
+                continue;
+            }
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct BrokenDesktopEntries {
+    entries: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for BrokenDesktopEntries {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let dirs = [
+            home_dir(config).join(".local/share/applications"),
+            PathBuf::from("/usr/local/share/applications"),
+        ];
+        for dir in dirs {
+            let entries = match fs::read_dir(&dir).await {
+                Ok(entries) => entries,
+                Err(_) => continue,
+            };
+            let mut entries = ReadDirStream::new(entries);
+            while let Some(entry) = entries.next().await {
+                let path = entry?.path();
+                if path.extension() != Some(OsStr::new("desktop")) {
+                    continue;
+                }
+
+                // `TryExec` is the one meant to be checked for existence, but
+                // most entries only include `Exec`.
+                let content = fs::read_to_string(&path).await?;
+                let exec = ini_value(&content, "TryExec").or_else(|| ini_value(&content, "Exec"));
+                if let Some(exec) = exec {
+                    if !command_exists(exec) {
+                        self.entries.push(path);
+                    }
+                }
+            }
+        }
+
+        let mut content = self
+            .entries
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.is_empty() {
+            content.push_str(&t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Broken desktop entries"),
+            content,
+            fix_available: !self.entries.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.entries).await?),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following files:"))?;
+        for entry in &self.entries {
+            writeln!(out, "* {}", entry.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for entry in &self.entries {
+            match remove_path(entry).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", entry.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct StaleAutostart {
+    entries: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for StaleAutostart {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let backend = pkg::backend(config);
+        let mut content = Vec::new();
+        if let Ok(entries) = fs::read_dir(home_dir(config).join(".config/autostart")).await {
+            let mut entries = ReadDirStream::new(entries);
+            while let Some(entry) = entries.next().await {
+                let path = entry?.path();
+                if path.extension() != Some(OsStr::new("desktop")) {
+                    continue;
+                }
+                let desktop = fs::read_to_string(&path).await?;
+                if ini_value(&desktop, "Hidden") == Some("true") {
+                    continue; // Already disabled
+                }
+
+                let exec = match ini_value(&desktop, "Exec") {
+                    Some(exec) => exec,
+                    None => continue,
+                };
+                let reason = match find_program(exec) {
+                    None => "missing executable",
+                    // Files under `/usr` are always managed by pacman, so if
+                    // no package owns it, it's a leftover.
+                    Some(program) if program.starts_with("/usr") => {
+                        if backend.is_owned(&program).await? {
+                            continue;
+                        }
+                        "uninstalled package"
+                    }
+                    Some(_) => continue,
+                };
+
+                content.push(format!("{} ({reason})", path.display()));
+                self.entries.push(path);
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Stale autostart entries"),
+            content: content.join("\n"),
+            fix_available: !self.entries.is_empty(),
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will ask to delete each of the following entries, or otherwise disable them with 'Hidden=true':"))?;
+        for entry in &self.entries {
+            writeln!(out, "* {}", entry.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for entry in &self.entries {
+            let res = if confirm_item(config, &t!("Delete {path}?", path = entry.display()))? {
+                remove_path(entry)
+                    .await
+                    .map(|bytes| fixed.add_removed(bytes))
+            } else {
+                // As specified by the XDG autostart spec, hidden entries are
+                // treated as if they didn't exist.
+                let mut desktop = fs::read_to_string(entry).await?;
+                if !desktop.ends_with('\n') {
+                    desktop.push('\n');
+                }
+                desktop.push_str("Hidden=true\n");
+                fs::write(entry, desktop).await
+            };
+            if let Err(e) = res {
+                fixed.add_failure(format!("couldn't update {}: {e}", entry.display()));
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct OrphanUserUnits {
+    /// Unit names and the file they're defined in
+    units: Vec<(String, PathBuf)>,
+}
+#[async_trait]
+impl CleanupCommand for OrphanUserUnits {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let cmd = Command::new("systemctl")
+            .arg("--user")
+            .arg("list-unit-files")
+            .arg("--state=enabled")
+            .arg("--no-legend")
+            .traced_output()
+            .await?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+
+        for unit in stdout
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+        {
+            // Outputs `FragmentPath=<file>` and `ExecStart={ path=<program> ;
+            // argv[]=... }`, where systemd already expanded the specifiers
+            // such as `%h` and looked the program up.
+            let cmd = Command::new("systemctl")
+                .arg("--user")
+                .arg("show")
+                .arg("--property=FragmentPath,ExecStart")
+                .arg(unit)
+                .traced_output()
+                .await?;
+            let stdout = String::from_utf8(cmd.stdout)?;
+            let (Some(fragment), Some(exec)) = (
+                ini_value(&stdout, "FragmentPath").filter(|path| !path.is_empty()),
+                ini_value(&stdout, "ExecStart"),
+            ) else {
+                continue;
+            };
+            let program = exec
+                .split_whitespace()
+                .find_map(|field| field.strip_prefix("path="));
+            if program.is_some_and(|program| !command_exists(program)) {
+                self.units.push((unit.to_string(), PathBuf::from(fragment)));
+            }
+        }
+
+        let mut content = self
+            .units
+            .iter()
+            .map(|(unit, path)| format!("{unit} ({})", path.display()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.is_empty() {
+            content.push_str(&t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Orphaned systemd user units"),
+            content,
+            fix_available: !self.units.is_empty(),
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will disable the following units, removing their files when they're in the home directory:"))?;
+        for (unit, path) in &self.units {
+            writeln!(out, "* {unit} ({})", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        let home = home_dir(config);
+        for (unit, path) in &self.units {
+            Command::new("systemctl")
+                .arg("--user")
+                .arg("disable")
+                .arg(unit)
+                .traced_status()
+                .await?;
+
+            // The ones outside the home are managed by pacman
+            if path.starts_with(&home) {
+                match remove_path(path).await {
+                    Ok(bytes) => fixed.add_removed(bytes),
+                    Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+                }
+            }
+        }
+
+        // So that systemd forgets about the removed units
+        Command::new("systemctl")
+            .arg("--user")
+            .arg("daemon-reload")
+            .traced_status()
+            .await?;
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct OllamaModels {
+    unused: Vec<String>,
+}
+#[async_trait]
+impl CleanupCommand for OllamaModels {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("Ollama models");
+        if !command_exists("ollama") {
+            return Ok(Output {
+                title,
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        // The models may be stored by the user or by the system service
+        let mut model_dirs = vec![
+            home_dir(config).join(".ollama/models"),
+            PathBuf::from("/var/lib/ollama/.ollama/models"),
+            PathBuf::from("/usr/share/ollama/.ollama/models"),
+        ];
+        if let Some(dir) = user_var(config, "OLLAMA_MODELS") {
+            model_dirs.insert(0, PathBuf::from(dir));
+        }
+
+        // Lines look like `llama2:latest  78e26419b446  3.8 GB  2 weeks ago`
+        let cmd = Command::new("ollama").arg("list").traced_output().await?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for line in stdout.lines().skip(1) {
+            let fields = line.split_whitespace().collect::<Vec<_>>();
+            let (name, size) = match fields[..] {
+                [name, _id, size, unit, ..] => {
+                    (name, parse_size(&format!("{size} {unit}")).unwrap_or(0))
+                }
+                _ => continue,
+            };
+
+            // Ollama reads the manifest of a model every time it's loaded, so
+            // its access time is the closest to a "last used" timestamp.
+            let (repo, tag) = name.split_once(':').unwrap_or((name, "latest"));
+            let manifest = match repo.split('/').count() {
+                1 => format!("registry.ollama.ai/library/{repo}/{tag}"),
+                2 => format!("registry.ollama.ai/{repo}/{tag}"),
+                _ => format!("{repo}/{tag}"),
+            };
+            let mut last_used = None;
+            for dir in &model_dirs {
+                last_used = accessed(dir.join("manifests").join(&manifest)).await;
+                if last_used.is_some() {
+                    break;
+                }
+            }
+
+            match last_used.map(days_since) {
+                Some(days) => {
+                    content.push(format!(
+                        "{name} ({}), last used {days} days ago",
+                        human_size(size)
+                    ));
+                    if days > config.max_age {
+                        self.unused.push(name.to_string());
+                        reclaimable += size;
+                    }
+                }
+                None => content.push(format!("{name} ({})", human_size(size))),
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will run the command:"))?;
+        writeln!(out, "  ollama rm {}", self.unused.join(" "))?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let status = Command::new("ollama")
+            .arg("rm")
+            .args(&self.unused)
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct MlModelCaches {
+    unused: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for MlModelCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let cache = xdg_dir(config, "XDG_CACHE_HOME", ".cache");
+        let hf_hub = user_var(config, "HF_HUB_CACHE")
+            .map(PathBuf::from)
+            .or_else(|| user_var(config, "HF_HOME").map(|home| PathBuf::from(home).join("hub")))
+            .unwrap_or_else(|| cache.join("huggingface/hub"));
+        let torch_hub = user_var(config, "TORCH_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| cache.join("torch"))
+            .join("hub");
+
+        // Each item is a path and its last access time. For Hugging Face,
+        // repositories are stored as `models--org--name` or
+        // `datasets--org--name`, and the files actually read are the ones in
+        // `blobs`, just like `huggingface-cli scan-cache` does.
+        let mut items = Vec::new();
+        for repo in read_dir_paths(&hf_hub).await {
+            let name = repo.file_name().unwrap_or_default().to_string_lossy();
+            if !["models--", "datasets--", "spaces--"]
+                .iter()
+                .any(|prefix| name.starts_with(prefix))
+            {
+                continue;
+            }
+
+            let mut last = None;
+            for blob in read_dir_paths(repo.join("blobs")).await {
+                last = last.max(accessed(blob).await);
+            }
+            items.push((repo, last));
+        }
+        // Torch hub stores repositories as directories and the pretrained
+        // weights inside `checkpoints`.
+        for node in read_dir_paths(&torch_hub).await {
+            if node.file_name() == Some(OsStr::new("checkpoints")) {
+                for checkpoint in read_dir_paths(&node).await {
+                    let last = accessed(&checkpoint).await;
+                    items.push((checkpoint, last));
+                }
+            } else if node.is_dir() {
+                let last = accessed(&node).await;
+                items.push((node, last));
+            }
+        }
+
+        let mut content = vec![format!(
+            "{} in {}",
+            human_size(disk_usage([&hf_hub]).await?),
+            hf_hub.display()
+        )];
+        content.push(format!(
+            "{} in {}",
+            human_size(disk_usage([&torch_hub]).await?),
+            torch_hub.display()
+        ));
+        let mut reclaimable = 0;
+        for (path, last) in items {
+            let bytes = disk_usage([&path]).await?;
+            let size = human_size(bytes);
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let days = last.map(days_since);
+            match days {
+                Some(days) => {
+                    content.push(format!("* {name} ({size}), last accessed {days} days ago"))
+                }
+                None => content.push(format!("* {name} ({size})")),
+            }
+            if days.is_some_and(|days| days > config.max_age) {
+                self.unused.push(path);
+                reclaimable += bytes;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Hugging Face and torch hub caches"),
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following items, unused in {days} days:",
+                days = config.max_age
+            )
+        )?;
+        for path in &self.unused {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.unused {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct ZoomCache {
+    dirs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for ZoomCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let zoom = home_dir(config).join(".zoom");
+        let total = disk_usage([&zoom]).await?;
+
+        let mut content = vec![format!("{} in {}", human_size(total), zoom.display())];
+        let mut reclaimable = 0;
+        for dir in ZOOM_DISPOSABLE {
+            let path = zoom.join(dir);
+            let size = disk_usage([&path]).await?;
+            if size > 0 {
+                content.push(format!("* {dir}: {}", human_size(size)));
+                self.dirs.push(path);
+                reclaimable += size;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Zoom cache and logs"),
+            content: content.join("\n"),
+            fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories, keeping the settings:")
+        )?;
+        for dir in &self.dirs {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.dirs {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+/// Reads the amount of bytes taken by deleted messages from a Thunderbird
+/// folder summary file. These use the Mork format, where columns are first
+/// declared as `(8A=expungedBytes)` and their hexadecimal values then
+/// referenced as `(^8A=1f4e)`. Later values override previous ones.
+fn mork_expunged_bytes(msf: &str) -> Option<u64> {
+    let decl = msf.find("=expungedBytes)")?;
+    let id = &msf[msf[..decl].rfind('(')? + 1..decl];
+    let value_start = msf.rfind(&format!("(^{id}="))? + id.len() + 3;
+    let value_end = value_start + msf[value_start..].find(')')?;
+
+    u64::from_str_radix(&msf[value_start..value_end], 16).ok()
+}
+
+#[derive(Default)]
+pub struct ThunderbirdCompaction {
+    profiles: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for ThunderbirdCompaction {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let base = home_dir(config).join(".thunderbird");
+        let ini = fs::read_to_string(base.join("profiles.ini"))
+            .await
+            .unwrap_or_default();
+        let profiles = ini
+            .lines()
+            .filter_map(|line| line.strip_prefix("Path="))
+            .map(|path| base.join(path)) // Absolute paths are kept as is
+            .collect::<Vec<_>>();
+
+        let mut content = Vec::new();
+        let mut total_savings = 0;
+        for profile in profiles {
+            let msfs =
+                find_files([profile.join("Mail"), profile.join("ImapMail")], &["*.msf"]).await?;
+            let mut savings = 0;
+            for msf in msfs {
+                let folder_size = match fs::metadata(msf.with_extension("")).await {
+                    Ok(meta) => meta.len(),
+                    Err(_) => continue,
+                };
+                let summary = String::from_utf8_lossy(&fs::read(&msf).await?).into_owned();
+                let expunged = mork_expunged_bytes(&summary).unwrap_or(0).min(folder_size);
+
+                // Small amounts aren't worth compacting
+                if expunged > 0 && expunged * 5 >= folder_size {
+                    savings += expunged;
+                    content.push(format!(
+                        "* {}: {} of {} deleted",
+                        msf.with_extension("").display(),
+                        human_size(expunged),
+                        human_size(folder_size)
+                    ));
+                }
+            }
+
+            if savings > 0 {
+                total_savings += savings;
+                self.profiles.push(profile);
+            }
+        }
+
+        content.insert(
+            0,
+            format!("{} could be saved by compacting", human_size(total_savings)),
+        );
+
+        Ok(Output {
+            title: t!("Thunderbird folder compaction"),
+            content: content.join("\n"),
+            fix_available: !self.profiles.is_empty(),
+            reclaimable_bytes: Some(total_savings),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("Thunderbird can't be compacted externally. This fix will enable automatic compaction without asking in the 'user.js' file of these profiles, which will take place the next time Thunderbird starts:"))?;
+        for profile in &self.profiles {
+            writeln!(out, "* {}", profile.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for profile in &self.profiles {
+            // The ones that were already set are rewritten in place, so that
+            // they aren't repeated every time the fix is applied
+            let path = profile.join("user.js");
+            let content = fs::read_to_string(&path).await.unwrap_or_default();
+            let mut missing = THUNDERBIRD_PREFS.to_vec();
+            let mut prefs = content
+                .lines()
+                .map(|line| {
+                    let set = THUNDERBIRD_PREFS.iter().find(|(name, _)| {
+                        line.trim_start()
+                            .starts_with(&format!("user_pref(\"{name}\","))
+                    });
+                    match set {
+                        Some((name, value)) => {
+                            missing.retain(|(missing, _)| missing != name);
+                            format!("user_pref(\"{name}\", {value});\n")
+                        }
+                        None => format!("{line}\n"),
+                    }
+                })
+                .collect::<String>();
+            for (name, value) in missing {
+                prefs.push_str(&format!("user_pref(\"{name}\", {value});\n"));
+            }
+            if let Err(e) = fs::write(&path, prefs).await {
+                fixed.add_failure(format!("couldn't update {}: {e}", path.display()));
+            }
+        }
+
+        // The folders are only compacted by Thunderbird later on
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct SyncthingVersions {
+    /// Versioning directories and the maximum age of the versions to keep
+    dirs: Vec<(PathBuf, u64)>,
+}
+#[async_trait]
+impl CleanupCommand for SyncthingVersions {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Newer versions store the configuration as state
+        let home = home_dir(config);
+        let mut xml = String::new();
+        for path in [
+            home.join(".local/state/syncthing/config.xml"),
+            home.join(".config/syncthing/config.xml"),
+        ] {
+            if let Ok(content) = fs::read_to_string(path).await {
+                xml = content;
+                break;
+            }
+        }
+
+        let mut content = Vec::new();
+        for folder in xml.split("<folder ").skip(1) {
+            let folder = folder.split("</folder>").next().unwrap_or_default();
+            let path = match xml_attr(folder, "path") {
+                Some(path) => PathBuf::from(path.replace("&amp;", "&")),
+                None => continue,
+            };
+
+            // The retention of the configured versioning policy, which may be
+            // either in days or in seconds. Versions are pruned by the fix if
+            // they're older than either it or the configured maximum age.
+            let param = |key: &str| {
+                folder
+                    .split("<param ")
+                    .skip(1)
+                    .find(|param| xml_attr(param, "key") == Some(key))
+                    .and_then(|param| xml_attr(param, "val"))
+                    .map(ToString::to_string)
+            };
+            let policy_days = param("cleanoutDays")
+                .and_then(|days| days.parse::<u64>().ok())
+                .or_else(|| {
+                    param("maxAge")
+                        .and_then(|secs| secs.parse::<u64>().ok())
+                        .map(|secs| secs / 86400)
+                })
+                .filter(|days| *days > 0);
+            let max_age = policy_days.map_or(config.max_age, |days| days.min(config.max_age));
+
+            let versions = match param("fsPath").filter(|path| !path.is_empty()) {
+                Some(fs_path) => path.join(fs_path),
+                None => path.join(".stversions"),
+            };
+            if fs::metadata(&versions).await.is_err() {
+                continue;
+            }
+
+            let size = disk_usage([&versions]).await?;
+            content.push(format!("{} ({})", versions.display(), human_size(size)));
+            self.dirs.push((versions, max_age));
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Syncthing file versions"),
+            content: content.join("\n"),
+            fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the file versions older than:")
+        )?;
+        for (dir, max_age) in &self.dirs {
+            let dir = dir.display();
+            writeln!(
+                out,
+                "* {}",
+                t!("{days} days in {dir}", days = max_age, dir = dir)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for (dir, max_age) in &self.dirs {
+            // Versions are named `file~20240101-120000.ext`, so they can be
+            // compared with the cutoff date as strings.
+            let cmd = Command::new("date")
+                .arg("--date")
+                .arg(format!("{max_age} days ago"))
+                .arg("+%Y%m%d-%H%M%S")
+                .traced_output()
+                .await?;
+            let cutoff = String::from_utf8(cmd.stdout)?;
+            let cutoff = cutoff.trim();
+
+            for file in find_files([dir], &["*~*"]).await? {
+                let name = file.file_name().unwrap_or_default().to_string_lossy();
+                let tag = match name.rsplit_once('~') {
+                    Some((_, tag)) if tag.len() >= 15 => &tag[..15],
+                    _ => continue,
+                };
+                if tag < cutoff {
+                    match remove_path(&file).await {
+                        Ok(bytes) => fixed.add_removed(bytes),
+                        Err(e) => {
+                            fixed.add_failure(format!("couldn't remove {}: {e}", file.display()))
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct DropboxCache {
+    caches: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for DropboxCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // The synced folders are listed in `info.json`, with entries like
+        // `"path": "/home/user/Dropbox"` for each account.
+        let home = home_dir(config);
+        let info = fs::read_to_string(home.join(".dropbox/info.json"))
+            .await
+            .unwrap_or_default();
+        let mut folders = info
+            .split("\"path\":")
+            .skip(1)
+            .filter_map(|rest| rest.trim_start().strip_prefix('"')?.split('"').next())
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        if folders.is_empty() {
+            folders.push(home.join("Dropbox"));
+        }
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for folder in folders {
+            let cache = folder.join(".dropbox.cache");
+            let size = disk_usage([&cache]).await?;
+            if size > 0 {
+                content.push(format!("{} in {}", human_size(size), cache.display()));
+                self.caches.push(cache);
+                reclaimable += size;
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Dropbox cache"),
+            content: content.join("\n"),
+            fix_available: !self.caches.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will stop the Dropbox client if it's running, empty the following directories, and start it again:"))?;
+        for cache in &self.caches {
+            writeln!(out, "* {}", cache.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        // The client may be writing to the cache while it's running
+        let running = Command::new("pgrep")
+            .arg("-x")
+            .arg("dropbox")
+            .stdout(Stdio::null())
+            .traced_status()
+            .await?
+            .success();
+        if running {
+            Command::new("dropbox").arg("stop").traced_status().await?;
+        }
+
+        for cache in &self.caches {
+            for entry in read_dir_paths(cache).await {
+                match remove_path(&entry).await {
+                    Ok(bytes) => fixed.add_removed(bytes),
+                    Err(e) => {
+                        fixed.add_failure(format!("couldn't remove {}: {e}", entry.display()))
+                    }
+                }
+            }
+        }
+
+        if running {
+            Command::new("dropbox").arg("start").traced_status().await?;
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct NextcloudConflicts {
+    files: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for NextcloudConflicts {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Both clients list the synced folders with lines such as
+        // `0\Folders\1\localPath=/home/user/Nextcloud/`
+        let config_dir = home_dir(config).join(".config");
+        let mut folders = Vec::new();
+        for cfg in ["Nextcloud/nextcloud.cfg", "ownCloud/owncloud.cfg"] {
+            let cfg = fs::read_to_string(config_dir.join(cfg))
+                .await
+                .unwrap_or_default();
+            folders.extend(
+                cfg.lines()
+                    .filter_map(|line| line.split_once("localPath="))
+                    .map(|(_, path)| PathBuf::from(path.trim())),
+            );
+        }
+
+        // Current clients name them `file (conflicted copy 2024-01-01
+        // 120000).txt`, whereas older ones use `file_conflict-20240101-120000.txt`
+        self.files = find_files(&folders, &["*conflicted copy*", "*_conflict-*"]).await?;
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for file in &self.files {
+            let meta = fs::metadata(file).await?;
+            reclaimable += meta.len();
+            let days = meta.modified().map(days_since).unwrap_or(0);
+            content.push(format!(
+                "{} ({}, {days} days old)",
+                file.display(),
+                human_size(meta.len())
+            ));
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Nextcloud sync conflicts"),
+            content: content.join("\n"),
+            fix_available: !self.files.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will ask to remove each of the conflict files. Make sure they've already been resolved."))?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for file in &self.files {
+            if !confirm_item(config, &t!("Remove {path}?", path = file.display()))? {
+                continue;
+            }
+            match remove_path(file).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", file.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+/// The name of an AppImage without its version and architecture, e.g.
+/// `Obsidian-1.4.16-x86_64.AppImage` becomes `obsidian`.
+fn appimage_name(file_name: &str) -> String {
+    file_name
+        .split(['-', '_', '.'])
+        .take_while(|token| {
+            let token = token.strip_prefix(['v', 'V']).unwrap_or(token);
+            !token.starts_with(|c: char| c.is_ascii_digit())
+                && !["x86", "amd64", "aarch64", "arm64", "linux", "appimage"]
+                    .contains(&token.to_lowercase().as_str())
+        })
+        .collect::<Vec<_>>()
+        .join("-")
+        .to_lowercase()
+}
+
+#[derive(Default)]
+pub struct OutdatedAppImages {
+    outdated: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for OutdatedAppImages {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let mut by_name = HashMap::<_, Vec<_>>::new();
+        for dir in [home.join("Applications"), home.join("bin")] {
+            for path in read_dir_paths(dir).await {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                if !file_name.to_lowercase().ends_with(".appimage") {
+                    continue;
+                }
+                let meta = fs::metadata(&path).await?;
+                let time = meta.modified()?;
+                by_name.entry(appimage_name(&file_name)).or_default().push((
+                    time,
+                    meta.len(),
+                    path,
+                ));
+            }
+        }
+
+        // Only the most recently downloaded one is kept for each application
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        let mut names = by_name.keys().cloned().collect::<Vec<_>>();
+        names.sort();
+        for name in names {
+            let mut versions = by_name.remove(&name).unwrap();
+            if versions.len() < 2 {
+                continue;
+            }
+            versions.sort();
+            let (_, _, newest) = versions.pop().unwrap();
+            content.push(format!("{name}: keeping {}", newest.display()));
+            for (_, size, path) in versions {
+                content.push(format!("* {} ({})", path.display(), human_size(size)));
+                self.outdated.push(path);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Outdated AppImage versions"),
+            content: content.join("\n"),
+            fix_available: !self.outdated.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following files:"))?;
+        for path in &self.outdated {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.outdated {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CupsSpool;
+#[async_trait]
+impl CleanupCommand for CupsSpool {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("CUPS spool");
+        let entries = match fs::read_dir(CUPS_SPOOL).await {
+            Ok(entries) => entries,
+            Err(e) => {
+                return Ok(Output {
+                    title,
+                    content: t!(
+                        "Couldn't read {path}: {error}",
+                        path = CUPS_SPOOL,
+                        error = e
+                    ),
+                    fix_available: false,
+                    reclaimable_bytes: None,
+                })
+            }
+        };
+
+        // Control files are named `c00001` and data files `d00001-001`
+        let mut old_jobs = 0;
+        let mut old_size = 0;
+        let mut entries = ReadDirStream::new(entries);
+        while let Some(entry) = entries.next().await {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let meta = entry.metadata().await?;
+            if !meta.is_file() || !name.starts_with(['c', 'd']) {
+                continue;
+            }
+            if meta.modified().map(days_since).unwrap_or(0) > config.max_age {
+                old_jobs += 1;
+                old_size += meta.len();
+            }
+        }
+
+        let size = disk_usage([CUPS_SPOOL]).await?;
+        let mut content = vec![
+            t!("{size} in {dir}", size = human_size(size), dir = CUPS_SPOOL),
+            t!(
+                "{count} job files older than {days} days ({size})",
+                count = old_jobs,
+                days = config.max_age,
+                size = human_size(old_size)
+            ),
+        ];
+
+        for dir in CUPS_PPD_DIRS {
+            for path in read_dir_paths(dir).await {
+                let size = fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+                if size > CUPS_PPD_MAX_SIZE {
+                    content.push(t!(
+                        "Oversized PPD: {path} ({size})",
+                        path = path.display(),
+                        size = human_size(size)
+                    ));
+                }
+            }
+        }
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: old_jobs > 0,
+            reclaimable_bytes: Some(old_size),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        writeln!(out, "  {sudo}cancel -a")?;
+        writeln!(
+            out,
+            "  {sudo}find {CUPS_SPOOL} -maxdepth 1 -type f -name '[cd]*' -mtime +{} -delete",
+            config.max_age
+        )?;
+        writeln!(
+            out,
+            "{}",
+            t!("The pending and printing jobs will be cancelled too")
+        )?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        let status = privilege::command(config, "cancel")
+            .await?
+            .arg("-a")
+            .traced_status()
+            .await?;
+        if !status.success() {
+            fixed.add_failure(format!(
+                "couldn't cancel the jobs: 'cancel -a' exited with {status}"
+            ));
+        }
+
+        let status = privilege::command(config, "find")
+            .await?
+            .arg(CUPS_SPOOL)
+            .arg("-maxdepth")
+            .arg("1")
+            .arg("-type")
+            .arg("f")
+            .arg("-name")
+            .arg("[cd]*")
+            .arg("-mtime")
+            .arg(format!("+{}", config.max_age))
+            .arg("-delete")
+            .traced_status()
+            .await?;
+        fixed.status = Some(status);
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct AuditLogs {
+    /// The rotated logs beyond the configured retention
+    excess: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for AuditLogs {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let title = t!("Audit logs");
+        let enabled = Command::new("systemctl")
+            .arg("is-enabled")
+            .arg("--quiet")
+            .arg("auditd")
+            .stderr(Stdio::null())
+            .traced_status()
+            .await?
+            .success();
+        if !enabled {
+            return Ok(Output {
+                title,
+                content: t!("(auditd not enabled)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        // Only readable by root
+        let mut logs = match fs::read_dir(AUDIT_LOGS).await {
+            Ok(entries) => ReadDirStream::new(entries),
+            Err(e) => {
+                return Ok(Output {
+                    title,
+                    content: t!(
+                        "Couldn't read {path}: {error}",
+                        path = AUDIT_LOGS,
+                        error = e
+                    ),
+                    fix_available: false,
+                    reclaimable_bytes: None,
+                })
+            }
+        };
+        let mut current = 0;
+        // By their number, where `audit.log.1` is the most recent one
+        let mut rotated = Vec::new();
+        while let Some(log) = logs.next().await {
+            let log = log?;
+            let name = log.file_name().to_string_lossy().into_owned();
+            let size = log.metadata().await?.len();
+            if name == "audit.log" {
+                current = size;
+            } else if let Some(number) = name.strip_prefix("audit.log.") {
+                rotated.push((number.parse::<usize>().ok(), log.path(), size));
+            }
+        }
+        rotated.sort();
+        let rotated_size = rotated.iter().map(|(_, _, size)| size).sum();
+
+        // auditd keeps `audit.log` and `num_logs - 1` rotated ones, so only
+        // the oldest ones beyond that are removed. There are more when
+        // `num_logs` was lowered or `max_log_file_action` isn't `rotate`.
+        let conf = fs::read_to_string(AUDITD_CONF).await.ok();
+        let conf_value = |key| conf.as_deref().and_then(|conf| ini_value(conf, key));
+        let num_logs = conf.as_ref().map(|_| {
+            conf_value("num_logs")
+                .map_or(Some(AUDITD_NUM_LOGS), |value| value.parse::<usize>().ok())
+        });
+        let mut excess_size = 0;
+        if let Some(Some(num_logs)) = num_logs {
+            let keep = num_logs.saturating_sub(1);
+            for (number, path, size) in &rotated {
+                if number.is_some_and(|number| number > keep) {
+                    excess_size += size;
+                    self.excess.push(path.clone());
+                }
+            }
+        }
+
+        let retention = match num_logs {
+            None => t!("Couldn't read {path}", path = AUDITD_CONF),
+            Some(num_logs) => t!(
+                "Configured retention: {count} logs of {size} MB",
+                count = num_logs.map_or_else(|| "?".to_string(), |n| n.to_string()),
+                size = conf_value("max_log_file").unwrap_or("?"),
+            ),
+        };
+        let content = [
+            format!("audit.log: {}", human_size(current)),
+            t!(
+                "{count} rotated logs: {size}",
+                count = rotated.len(),
+                size = human_size(rotated_size)
+            ),
+            retention,
+            t!(
+                "{count} beyond it: {size}",
+                count = self.excess.len(),
+                size = human_size(excess_size)
+            ),
+        ];
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.excess.is_empty(),
+            reclaimable_bytes: Some(excess_size),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(out, "{}", t!("This fix will remove the rotated logs beyond the configured retention with '{command}':", command = format!("{sudo}rm")))?;
+        for log in &self.excess {
+            writeln!(out, "* {}", log.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "rm")
+            .await?
+            .arg("-f")
+            .arg("--")
+            .args(&self.excess)
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct PartialDownloads {
+    files: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for PartialDownloads {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        // Interrupted downloads leave `.part` files, and sometimes empty
+        // packages, which `paccache` doesn't consider.
+        let mut size = 0;
+        for path in read_dir_paths(PACMAN_CACHE).await {
+            let meta = match fs::symlink_metadata(&path).await {
+                Ok(meta) if meta.is_file() => meta,
+                _ => continue,
+            };
+            if path.extension() == Some(OsStr::new("part")) || meta.len() == 0 {
+                size += meta.len();
+                self.files.push(path);
+            }
+        }
+
+        Ok(Output {
+            title: t!("Partial downloads in the pacman cache"),
+            content: format!("{} files ({})", self.files.len(), human_size(size)),
+            fix_available: !self.files.is_empty(),
+            reclaimable_bytes: Some(size),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following files with '{command}':",
+                command = format!("{sudo}rm")
+            )
+        )?;
+        for file in &self.files {
+            writeln!(out, "* {}", file.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "rm")
+            .await?
+            .arg("-f")
+            .arg("--")
+            .args(&self.files)
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct PlasmaCache {
+    paths: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for PlasmaCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let mut sizes = HashMap::<&str, u64>::new();
+        let cache = home_dir(config).join(".cache");
+        let entries = read_dir_paths(&cache).await;
+        let names = entries
+            .iter()
+            .filter_map(|path| path.file_name()?.to_str())
+            .collect::<Vec<_>>();
+        // The service cache of the previous major version is left behind
+        // after upgrading to Plasma 6.
+        let plasma6 = names.iter().any(|name| name.starts_with("ksycoca6"));
+
+        for path in &entries {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let kind = if name.starts_with("ksycoca5") && plasma6 {
+                "ksycoca (stale, Plasma 5)"
+            } else if name.starts_with("ksycoca") {
+                "ksycoca"
+            } else if name.starts_with("plasma-svgelements") {
+                "plasma-svgelements"
+            } else if name.ends_with(".kcache") {
+                "kcache"
+            } else if name.starts_with("plasma") {
+                "other plasma"
+            } else {
+                continue;
+            };
+
+            *sizes.entry(kind).or_default() += disk_usage([path]).await?;
+            self.paths.push(path.clone());
+        }
+
+        let reclaimable = sizes.values().sum();
+        let mut content = sizes
+            .into_iter()
+            .map(|(kind, size)| format!("{kind}: {}", human_size(size)))
+            .collect::<Vec<_>>();
+        content.sort();
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("KDE Plasma cache"),
+            content: content.join("\n"),
+            fix_available: !self.paths.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following files, which are regenerated on the next login:"))?;
+        for path in &self.paths {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.paths {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct SessionLogs {
+    truncate: Vec<PathBuf>,
+    remove: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for SessionLogs {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let max_size = config.max_log_size * 1024 * 1024;
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+
+        // The current log is still open by the session, so it's truncated
+        // rather than removed.
+        let mut logs = vec![
+            (home.join(".xsession-errors"), false),
+            (home.join(".xsession-errors.old"), true),
+        ];
+        // Xorg logs are stale when they're backups or haven't been written to
+        // in a while.
+        for path in read_dir_paths(home.join(".local/share/xorg")).await {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if !name.starts_with("Xorg.") {
+                continue;
+            }
+            let old = name.ends_with(".old")
+                || modified(&path).await.map(days_since).unwrap_or(0) > config.max_age;
+            logs.push((path, old));
+        }
+
+        for (path, stale) in logs {
+            let size = match fs::metadata(&path).await {
+                Ok(meta) => meta.len(),
+                Err(_) => continue,
+            };
+            content.push(format!("{} ({})", path.display(), human_size(size)));
+            if size <= max_size {
+                continue;
+            }
+            reclaimable += size;
+            if stale {
+                self.remove.push(path);
+            } else {
+                self.truncate.push(path);
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("X session logs"),
+            content: content.join("\n"),
+            fix_available: !self.truncate.is_empty() || !self.remove.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        for path in &self.truncate {
+            let path = path.display();
+            writeln!(out, "{}", t!("This fix will truncate {path}", path = path))?;
+        }
+        for path in &self.remove {
+            let path = path.display();
+            writeln!(out, "{}", t!("This fix will remove {path}", path = path))?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.truncate {
+            if let Err(e) = File::create(path).await {
+                fixed.add_failure(format!("couldn't truncate {}: {e}", path.display()));
+            }
+        }
+        for path in &self.remove {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CrashLogs {
+    files: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for CrashLogs {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        self.files = find_files([home_dir(config)], &CRASH_LOGS).await?;
+
+        // Summarized by kind of log
+        let mut summary = CRASH_LOGS.map(|pattern| (pattern, 0, 0));
+        for file in &self.files {
+            let name = file.file_name().unwrap_or_default().to_string_lossy();
+            let size = fs::metadata(file).await.map(|m| m.len()).unwrap_or(0);
+            let kind = summary.iter_mut().find(|(pattern, _, _)| {
+                let (prefix, suffix) = pattern.split_once('*').unwrap_or((pattern, ""));
+                name.starts_with(prefix) && name.ends_with(suffix)
+            });
+            if let Some((_, count, total)) = kind {
+                *count += 1;
+                *total += size;
+            }
+        }
+
+        let reclaimable = summary.iter().map(|(_, _, size)| size).sum();
+        let mut content = summary
+            .iter()
+            .filter(|(_, count, _)| *count > 0)
+            .map(|(pattern, count, size)| {
+                format!("{pattern}: {count} files ({})", human_size(*size))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        if content.is_empty() {
+            content.push_str(&t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Crash and debug logs"),
+            content,
+            fix_available: !self.files.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following files:"))?;
+        for file in &self.files {
+            writeln!(out, "* {}", file.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for file in &self.files {
+            match remove_path(file).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", file.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct LutrisRunners {
+    unused: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for LutrisRunners {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let data = home.join(".local/share/lutris");
+
+        // The versions used are referenced by name in the game and runner
+        // configs, e.g. `version: lutris-GE-Proton8-26-x86_64`. Their location
+        // depends on the version of Lutris.
+        let mut configs = String::new();
+        for dir in [
+            home.join(".config/lutris/games"),
+            home.join(".config/lutris/runners"),
+            data.join("games"),
+            data.join("runners"),
+            home.join(".config/lutris"),
+            data.clone(),
+        ] {
+            for path in read_dir_paths(dir).await {
+                if path.extension() == Some(OsStr::new("yml")) {
+                    configs.push_str(&fs::read_to_string(path).await.unwrap_or_default());
+                }
+            }
+        }
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for kind in ["runners/wine", "runtime/dxvk", "runtime/vkd3d"] {
+            let mut versions = Vec::new();
+            for path in read_dir_paths(data.join(kind)).await {
+                if path.is_dir() {
+                    versions.push((modified(&path).await, path));
+                }
+            }
+            // The newest one is kept even if it's not referenced, as it's the
+            // default when games don't specify a version.
+            versions.sort();
+            versions.pop();
+
+            for (_, path) in versions {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                if configs.contains(name.as_ref()) {
+                    continue;
+                }
+                let size = disk_usage([&path]).await?;
+                content.push(format!("{kind}/{name} ({})", human_size(size)));
+                self.unused.push(path);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Unused Lutris runner versions"),
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for path in &self.unused {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.unused {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CompletionDumps {
+    stale: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for CompletionDumps {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Outputs `zsh 5.9 (x86_64-pc-linux-gnu)`
+        let zsh_version = match Command::new("zsh").arg("--version").traced_output().await {
+            Ok(cmd) => String::from_utf8(cmd.stdout)?
+                .split_whitespace()
+                .nth(1)
+                .map(ToString::to_string),
+            Err(_) => None, // Not installed, all of them are stale
+        };
+
+        // Dumps are named `.zcompdump-<host>-<version>` by default, and their
+        // compiled versions end in `.zwc`. Frameworks like oh-my-zsh keep them
+        // in the cache instead.
+        let home = home_dir(config);
+        let mut dirs = vec![home.clone(), home.join(".cache"), home.join(".cache/zsh")];
+        if let Some(zdotdir) = user_var(config, "ZDOTDIR") {
+            dirs.push(PathBuf::from(zdotdir));
+        }
+        let mut content = Vec::new();
+        for dir in dirs {
+            for path in read_dir_paths(dir).await {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let name = name.trim_start_matches('.');
+                if !name.starts_with("zcompdump-") {
+                    continue;
+                }
+                let version = name.trim_end_matches(".zwc").rsplit('-').next();
+                if version.is_some() && version == zsh_version.as_deref() {
+                    continue;
+                }
+                content.push(format!("{} (stale zsh dump)", path.display()));
+                self.stale.push(path);
+            }
+        }
+
+        for path in read_dir_paths(home.join(".cache/bash-completion")).await {
+            let size = fs::metadata(&path).await.map(|m| m.len()).unwrap_or(0);
+            if size > COMPLETION_CACHE_MAX_SIZE {
+                content.push(format!(
+                    "{} (oversized bash cache, {})",
+                    path.display(),
+                    human_size(size)
+                ));
+                self.stale.push(path);
+            }
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Stale shell completion dumps"),
+            content: content.join("\n"),
+            fix_available: !self.stale.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.stale).await?),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following files:"))?;
+        for path in &self.stale {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.stale {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct LocalState {
+    flagged: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for LocalState {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let names = installed_app_names(config).await?;
+        let max_size = config.max_log_size * 1024 * 1024;
+
+        let mut entries = Vec::new();
+        for path in read_dir_paths(xdg_dir(config, "XDG_STATE_HOME", ".local/state")).await {
+            let size = disk_usage([&path]).await?;
+            let days = modified(&path).await.map(days_since).unwrap_or(0);
+            entries.push((size, days, path));
+        }
+        entries.sort_by_key(|(size, _, _)| Reverse(*size));
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (size, days, path) in entries {
+            // History files are named after their program, e.g. `lesshst` or
+            // `python_history`.
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_lowercase();
+            let app = ["_history", "-history", "hst", ".log"]
+                .iter()
+                .find_map(|suffix| name.strip_suffix(suffix))
+                .unwrap_or(&name);
+
+            let mut line = format!(
+                "{} ({}, modified {days} days ago)",
+                path.display(),
+                human_size(size)
+            );
+            let reason = if !names.contains(app) && !APP_DIR_IGNORED.contains(&app) {
+                Some("uninstalled")
+            } else if size > max_size {
+                Some("oversized")
+            } else {
+                None
+            };
+            if let Some(reason) = reason {
+                line.push_str(&format!(" [{reason}]"));
+                self.flagged.push(path);
+                reclaimable += size;
+            }
+            content.push(line);
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Application state in ~/.local/state"),
+            content: content.join("\n"),
+            fix_available: !self.flagged.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will ask to remove each of the following entries:")
+        )?;
+        for path in &self.flagged {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.flagged {
+            if !confirm_item(config, &t!("Remove {path}?", path = path.display()))? {
+                continue;
+            }
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CargoBinaries {
+    outdated: Vec<String>,
+    unused: Vec<String>,
+}
+#[async_trait]
+impl CleanupCommand for CargoBinaries {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let cargo_home = user_var(config, "CARGO_HOME")
+            .map_or_else(|| home_dir(config).join(".cargo"), PathBuf::from);
+        let crates = fs::read_to_string(cargo_home.join(".crates.toml"))
+            .await
+            .unwrap_or_default();
+
+        // Entries look like `"ripgrep 13.0.0 (registry+https://...)" = ["rg"]`
+        let mut content = Vec::new();
+        let mut unused_bins = Vec::new();
+        for line in crates.lines() {
+            let (krate, bins) = match line.split_once("\" = [") {
+                Some((krate, bins)) => (krate.trim_start_matches('"'), bins.trim_end_matches(']')),
+                None => continue,
+            };
+            let (name, version, source) = match krate.splitn(3, ' ').collect::<Vec<_>>()[..] {
+                [name, version, source] => (name, version, source),
+                _ => continue,
+            };
+            let bins = bins
+                .split(", ")
+                .map(|bin| bin.trim_matches('"'))
+                .collect::<Vec<_>>();
+
+            // Only crates from crates.io can be checked for updates. The
+            // search outputs lines like `ripgrep = "14.1.0"    # Description`.
+            let mut latest = None;
+            if source.starts_with("(registry+") {
+                let cmd = Command::new("cargo")
+                    .arg("search")
+                    .arg("--limit")
+                    .arg("1")
+                    .arg(name)
+                    .stderr(Stdio::null())
+                    .traced_output()
+                    .await?;
+                let stdout = String::from_utf8(cmd.stdout)?;
+                latest = stdout
+                    .lines()
+                    .next()
+                    .and_then(|line| line.strip_prefix(&format!("{name} = \"")))
+                    .and_then(|rest| rest.split('"').next())
+                    .map(ToString::to_string);
+            }
+
+            // A tool is used when any of its binaries has been run recently
+            let mut last_used = None;
+            for bin in &bins {
+                last_used = last_used.max(accessed(cargo_home.join("bin").join(bin)).await);
+            }
+            let days = last_used.map(days_since);
+
+            let mut line = format!("{name} {version} ({})", bins.join(", "));
+            match latest {
+                Some(latest) if latest != version => {
+                    line.push_str(&format!(", {latest} available"));
+                    self.outdated.push(name.to_string());
+                }
+                _ => {}
+            }
+            if let Some(days) = days {
+                line.push_str(&format!(", last used {days} days ago"));
+                if days > config.max_age {
+                    self.unused.push(name.to_string());
+                    unused_bins.extend(bins.iter().map(|bin| cargo_home.join("bin").join(bin)));
+                }
+            }
+            content.push(line);
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Binaries installed with cargo"),
+            content: content.join("\n"),
+            fix_available: !self.outdated.is_empty() || !self.unused.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&unused_bins).await?),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        if !self.outdated.is_empty() {
+            writeln!(out, "{}", t!("This fix will run the command:"))?;
+            writeln!(out, "  cargo install {}", self.outdated.join(" "))?;
+        }
+        if !self.unused.is_empty() {
+            writeln!(
+                out,
+                "{}",
+                t!(
+                    "It will also ask to uninstall the crates unused in {days} days:",
+                    days = config.max_age
+                )
+            )?;
+            for name in &self.unused {
+                writeln!(out, "* {name}")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        if !self.outdated.is_empty() {
+            let status = Command::new("cargo")
+                .arg("install")
+                .args(&self.outdated)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult::status(status));
+        }
+
+        for name in &self.unused {
+            if !confirm_item(config, &t!("Uninstall {name}?", name = name))? {
+                continue;
+            }
+            let status = Command::new("cargo")
+                .arg("uninstall")
+                .arg(name)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult {
+                removed: 1,
+                ..FixResult::status(status)
+            });
+        }
+
+        Ok(fixed)
+    }
+}
+
+/// Whether a `wandb` directory has local runs, i.e., `run-*` or
+/// `offline-run-*` directories.
+async fn has_wandb_runs(dir: &Path) -> bool {
+    read_dir_paths(dir).await.iter().any(|path| {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        path.is_dir() && (name.starts_with("run-") || name.starts_with("offline-run-"))
+    })
+}
+
+#[derive(Default)]
+pub struct MlToolCaches {
+    old: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for MlToolCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let mut dirs = vec![
+            home.join(".keras/datasets"),
+            home.join(".keras/models"),
+            home.join(".cache/torch_extensions"),
+            home.join(".cache/wandb"),
+            home.join(".local/share/wandb"),
+        ];
+        // Local runs of wandb and TensorBoard logs from PyTorch Lightning are
+        // stored in the directory of each project. The installed Python
+        // packages are skipped, since the one of wandb has the same name.
+        for dir in find_dirs([&home], &["wandb", "lightning_logs", "tb_logs"]).await? {
+            let installed = dir.components().any(|component| {
+                let component = component.as_os_str();
+                component == "site-packages" || component == "dist-packages"
+            });
+            if installed || (dir.ends_with("wandb") && !has_wandb_runs(&dir).await) {
+                continue;
+            }
+            dirs.push(dir);
+        }
+
+        let mut content = Vec::new();
+        for dir in dirs {
+            let size = disk_usage([&dir]).await?;
+            if size == 0 {
+                continue;
+            }
+
+            // Each entry is a dataset, an extension build, a run...
+            let mut old = 0;
+            for entry in read_dir_paths(&dir).await {
+                if modified(&entry).await.map(days_since).unwrap_or(0) > config.max_age {
+                    old += 1;
+                    self.old.push(entry);
+                }
+            }
+            content.push(format!(
+                "{} ({}, {old} entries older than {} days)",
+                dir.display(),
+                human_size(size),
+                config.max_age
+            ));
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Machine learning tool caches"),
+            content: content.join("\n"),
+            fix_available: !self.old.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.old).await?),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following entries, older than {days} days:",
+                days = config.max_age
+            )
+        )?;
+        for path in &self.old {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.old {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct SdkmanVersions {
+    sdkman_dir: PathBuf,
+    /// Pairs of candidate and version
+    old: Vec<(String, String)>,
+}
+#[async_trait]
+impl CleanupCommand for SdkmanVersions {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        self.sdkman_dir = user_var(config, "SDKMAN_DIR")
+            .map_or_else(|| home_dir(config).join(".sdkman"), PathBuf::from);
+
+        // Each candidate has a `current` symlink to the default version. It's
+        // missing after `sdk use` or installing one by hand, and then it's
+        // unknown which of them are still used.
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for candidate in read_dir_paths(self.sdkman_dir.join("candidates")).await {
+            let name = candidate
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let Some(current) = fs::read_link(candidate.join("current"))
+                .await
+                .ok()
+                .and_then(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            else {
+                continue;
+            };
+
+            for version in read_dir_paths(&candidate).await {
+                let version_name = version
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                if version_name == "current" || version_name == current {
+                    continue;
+                }
+                let size = disk_usage([&version]).await?;
+                content.push(format!("{name} {version_name} ({})", human_size(size)));
+                self.old.push((name.clone(), version_name));
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Old SDKMAN versions"),
+            content: content.join("\n"),
+            fix_available: !self.old.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        for (candidate, version) in &self.old {
+            writeln!(out, "  sdk uninstall {candidate} {version}")?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        // `sdk` is a shell function, so SDKMAN has to be loaded first
+        let init = self.sdkman_dir.join("bin/sdkman-init.sh");
+        let mut fixed = FixResult::default();
+        for (candidate, version) in &self.old {
+            let status = Command::new("bash")
+                .arg("-c")
+                .arg("source \"$0\" && sdk uninstall \"$1\" \"$2\"")
+                .arg(&init)
+                .arg(candidate)
+                .arg(version)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult {
+                removed: 1,
+                ..FixResult::status(status)
+            });
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct DevContainers {
+    /// Pairs of container name and image
+    unused: Vec<(String, String)>,
+}
+#[async_trait]
+impl CleanupCommand for DevContainers {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("Distrobox and toolbox containers");
+        if !command_exists("podman") {
+            return Ok(Output {
+                title,
+                content: t!("(podman not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for label in ["manager=distrobox", "com.github.containers.toolbox=true"] {
+            let cmd = Command::new("podman")
+                .arg("ps")
+                .arg("--all")
+                .arg("--size")
+                .arg("--filter")
+                .arg(format!("label={label}"))
+                .arg("--format")
+                .arg("{{.Names}}\t{{.Image}}\t{{.Size}}")
+                .traced_output()
+                .await?;
+            let stdout = String::from_utf8(cmd.stdout)?;
+
+            for line in stdout.lines() {
+                let (name, image, size) = match line.split('\t').collect::<Vec<_>>()[..] {
+                    [name, image, size] => (name, image, size),
+                    _ => continue,
+                };
+
+                // Entering a container starts it, so the last time it was
+                // entered is roughly when it was last started, or created if
+                // it never was.
+                let cmd = Command::new("podman")
+                    .arg("container")
+                    .arg("inspect")
+                    .arg("--format")
+                    .arg("{{.State.StartedAt.Unix}} {{.Created.Unix}}")
+                    .arg(name)
+                    .traced_output()
+                    .await?;
+                let stdout = String::from_utf8(cmd.stdout)?;
+                let last = stdout
+                    .split_whitespace()
+                    .filter_map(|secs| secs.parse::<u64>().ok())
+                    .max()
+                    .map(|secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+                let days = last.map(days_since).unwrap_or(0);
+
+                content.push(format!(
+                    "{name} ({image}, {size}), last entered {days} days ago"
+                ));
+                if days > config.max_age {
+                    self.unused.push((name.to_string(), image.to_string()));
+                    // Only the writable layer, e.g. `12kB (virtual 1.2GB)`,
+                    // as the image may be shared with other containers
+                    reclaimable += parse_size(size).unwrap_or(0);
+                }
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        for (name, image) in &self.unused {
+            writeln!(out, "  podman rm --force {name}")?;
+            writeln!(out, "  podman rmi {image}")?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for (name, image) in &self.unused {
+            let status = Command::new("podman")
+                .arg("rm")
+                .arg("--force")
+                .arg(name)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult {
+                removed: 1,
+                ..FixResult::status(status)
+            });
+
+            // This will fail if other containers still use the image, which
+            // is fine
+            Command::new("podman")
+                .arg("rmi")
+                .arg(image)
+                .traced_status()
+                .await?;
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct WaydroidData {
+    removable: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for WaydroidData {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("Waydroid images and data");
+        if !command_exists("waydroid") {
+            return Ok(Output {
+                title,
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        let base = Path::new(WAYDROID_DIR);
+        let overlay = home_dir(config).join(".local/share/waydroid/data");
+        let images = disk_usage([base.join("images")]).await?;
+        let downloads = base.join("cache_http");
+        let downloads_size = disk_usage([&downloads]).await?;
+        let data = disk_usage([&overlay]).await?;
+        let mut content = vec![
+            t!(
+                "System and vendor images: {size}",
+                size = human_size(images)
+            ),
+            t!("Android data: {size}", size = human_size(data)),
+            t!(
+                "Downloaded images: {size}",
+                size = human_size(downloads_size)
+            ),
+        ];
+        if downloads_size > 0 {
+            self.removable.push(downloads);
+        }
+
+        // The installed packages, including the system ones and those without
+        // a launcher, can only be listed as root while the session is running.
+        // Lines look like `package:org.fdroid.fdroid`.
+        let installed = if privilege::is_root() {
+            let cmd = Command::new("waydroid")
+                .arg("shell")
+                .arg("pm")
+                .arg("list")
+                .arg("packages")
+                .stderr(Stdio::null())
+                .traced_output()
+                .await?;
+            String::from_utf8(cmd.stdout)?
+                .lines()
+                .filter_map(|line| line.strip_prefix("package:"))
+                .map(|pkg| pkg.trim().to_string())
+                .collect::<HashSet<_>>()
+        } else {
+            HashSet::new()
+        };
+        if installed.is_empty() {
+            content.push(t!(
+                "Run as root with the Waydroid session started to check for data of removed apps"
+            ));
+        } else {
+            for path in read_dir_paths(overlay.join("data")).await {
+                let pkg = path.file_name().unwrap_or_default().to_string_lossy();
+                if installed.contains(pkg.as_ref()) {
+                    continue;
+                }
+                content.push(t!("Data of removed app {pkg}", pkg = pkg));
+                self.removable.push(path);
+            }
+        }
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.removable.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.removable).await?),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following directories with '{command}':",
+                command = format!("{sudo}rm")
+            )
+        )?;
+        for path in &self.removable {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        // Both are owned by root or Android's users, and can't be restored
+        let mut fixed = FixResult::default();
+        for path in &self.removable {
+            if !confirm_item(config, &t!("Remove {path}?", path = path.display()))? {
+                continue;
+            }
+
+            let status = privilege::command(config, "rm")
+                .await?
+                .arg("-rf")
+                .arg("--")
+                .arg(path)
+                .traced_status()
+                .await?;
+            if status.success() {
+                fixed.removed += 1;
+            }
+            fixed.add(&FixResult::status(status));
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct StaleBootEntries {
+    entries: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for StaleBootEntries {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let mut content = Vec::new();
+        for esp in ESP_PATHS.iter().map(Path::new) {
+            for entry in read_dir_paths(esp.join("loader/entries")).await {
+                if entry.extension() != Some(OsStr::new("conf")) {
+                    continue;
+                }
+
+                // Entries have lines like `linux /vmlinuz-linux`, with the
+                // paths relative to the root of the ESP
+                let conf = fs::read_to_string(&entry).await.unwrap_or_default();
+                let missing = conf
+                    .lines()
+                    .filter_map(|line| line.trim().split_once(char::is_whitespace))
+                    .filter(|(key, _)| ["linux", "initrd", "efi", "devicetree"].contains(key))
+                    .map(|(_, file)| file.trim())
+                    .filter(|file| !esp.join(file.trim_start_matches('/')).exists())
+                    .collect::<Vec<_>>();
+                if !missing.is_empty() {
+                    content.push(format!(
+                        "{} (missing {})",
+                        entry.display(),
+                        missing.join(", ")
+                    ));
+                    self.entries.push(entry);
+                }
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Stale systemd-boot entries"),
+            content: content.join("\n"),
+            fix_available: !self.entries.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.entries).await?),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following entries with '{command}':",
+                command = format!("{sudo}rm")
+            )
+        )?;
+        for entry in &self.entries {
+            writeln!(out, "* {}", entry.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "rm")
+            .await?
+            .arg("--")
+            .args(&self.entries)
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct OrphanPacmanHooks {
+    hooks: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for OrphanPacmanHooks {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let mut content = Vec::new();
+        for hook in read_dir_paths(PACMAN_HOOKS).await {
+            if hook.extension() != Some(OsStr::new("hook")) {
+                continue;
+            }
+            let conf = fs::read_to_string(&hook).await.unwrap_or_default();
+            if let Some(exec) = ini_value(&conf, "Exec") {
+                if !command_exists(exec) {
+                    content.push(format!("{} ({exec})", hook.display()));
+                    self.hooks.push(hook);
+                }
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Orphaned pacman hooks"),
+            content: content.join("\n"),
+            fix_available: !self.hooks.is_empty(),
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will ask to delete each of the following hooks, or otherwise disable them by renaming them to '.hook.disabled':"))?;
+        for hook in &self.hooks {
+            writeln!(out, "* {}", hook.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        // Pacman only loads the files ending in `.hook`
+        let mut fixed = FixResult::default();
+        for hook in &self.hooks {
+            let status = if confirm_item(config, &t!("Delete {path}?", path = hook.display()))? {
+                privilege::command(config, "rm")
+                    .await?
+                    .arg("--")
+                    .arg(hook)
+                    .traced_status()
+                    .await?
+            } else {
+                privilege::command(config, "mv")
+                    .await?
+                    .arg("--")
+                    .arg(hook)
+                    .arg(hook.with_extension("hook.disabled"))
+                    .traced_status()
+                    .await?
+            };
+            fixed.add(&FixResult::status(status));
+        }
+
+        Ok(fixed)
+    }
+}
+
+/// Groups build artifacts by the project they belong to, which is the closest
+/// ancestor (up to `depth` levels above) containing any of the marker files.
+/// Artifacts outside a project are discarded.
+async fn artifacts_by_project(
+    artifacts: Vec<PathBuf>,
+    markers: &[&str],
+    depth: usize,
+) -> Result<Vec<(PathBuf, u64, Vec<PathBuf>)>> {
+    let mut projects = HashMap::<PathBuf, Vec<PathBuf>>::new();
+    for artifact in artifacts {
+        let project = artifact
+            .ancestors()
+            .skip(1)
+            .take(depth)
+            .find(|dir| markers.iter().any(|marker| dir.join(marker).exists()));
+        if let Some(project) = project {
+            projects
+                .entry(project.to_path_buf())
+                .or_default()
+                .push(artifact);
+        }
+    }
+
+    let mut sized = Vec::with_capacity(projects.len());
+    for (project, artifacts) in projects {
+        let size = disk_usage(&artifacts).await?;
+        sized.push((project, size, artifacts));
+    }
+    sized.sort_by_key(|(_, size, _)| Reverse(*size));
+
+    Ok(sized)
+}
+
+#[derive(Default)]
+pub struct PythonArtifacts {
+    dirs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for PythonArtifacts {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let artifacts = find_dirs(
+            project_roots(config),
+            &["build", "dist", "*.egg-info", ".tox"],
+        )
+        .await?;
+        // `egg-info` may also be inside `src`
+        let projects = artifacts_by_project(
+            artifacts,
+            &["pyproject.toml", "setup.py", "setup.cfg", "tox.ini"],
+            2,
+        )
+        .await?;
+
+        let mut total = 0;
+        let mut content = Vec::new();
+        for (project, size, dirs) in projects {
+            total += size;
+            content.push(format!("{} ({})", project.display(), human_size(size)));
+            self.dirs.extend(dirs);
+        }
+        content.insert(0, format!("{} in total", human_size(total)));
+
+        Ok(Output {
+            title: t!("Python build artifacts"),
+            content: content.join("\n"),
+            fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: Some(total),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for dir in &self.dirs {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.dirs {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct JsBuildOutputs {
+    projects: Vec<(PathBuf, Vec<PathBuf>)>,
+}
+#[async_trait]
+impl CleanupCommand for JsBuildOutputs {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let outputs = find_dirs(
+            project_roots(config),
+            &[
+                ".next",
+                ".nuxt",
+                ".svelte-kit",
+                "dist",
+                ".turbo",
+                ".parcel-cache",
+            ],
+        )
+        .await?;
+        let projects = artifacts_by_project(outputs, &["package.json"], 1).await?;
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (project, size, dirs) in projects {
+            let mut last = None;
+            for dir in &dirs {
+                last = last.max(modified(dir).await);
+            }
+            let days = last.map(days_since).unwrap_or(0);
+            content.push(format!(
+                "{} ({}, built {days} days ago)",
+                project.display(),
+                human_size(size)
+            ));
+            self.projects.push((project, dirs));
+            reclaimable += size;
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("JavaScript build outputs"),
+            content: content.join("\n"),
+            fix_available: !self.projects.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will ask to remove the build outputs of each project:")
+        )?;
+        for (project, dirs) in &self.projects {
+            writeln!(out, "* {}", project.display())?;
+            for dir in dirs {
+                writeln!(out, "  - {}", dir.display())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for (project, dirs) in &self.projects {
+            if !confirm_item(config, &t!("Clean {path}?", path = project.display()))? {
+                continue;
+            }
+            for dir in dirs {
+                match remove_path(dir).await {
+                    Ok(bytes) => fixed.add_removed(bytes),
+                    Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+                }
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct GradleBuilds {
+    old: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for GradleBuilds {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let builds = find_dirs(project_roots(config), &["build"]).await?;
+        // Modules may be nested, as in `features/login/build`
+        let projects =
+            artifacts_by_project(builds, &["settings.gradle", "settings.gradle.kts"], 3).await?;
+
+        let mut content = Vec::new();
+        for (project, size, dirs) in projects {
+            let mut old = 0;
+            for dir in dirs {
+                if modified(&dir).await.map(days_since).unwrap_or(0) > config.max_age {
+                    old += 1;
+                    self.old.push(dir);
+                }
+            }
+            content.push(format!(
+                "{} ({}, {old} builds older than {} days)",
+                project.display(),
+                human_size(size),
+                config.max_age
+            ));
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Gradle project build directories"),
+            content: content.join("\n"),
+            fix_available: !self.old.is_empty(),
+            reclaimable_bytes: Some(disk_usage(&self.old).await?),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for dir in &self.old {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.old {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct GameEngineCaches {
+    dirs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for GameEngineCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let candidates = find_dirs(
+            project_roots(config),
+            &["Library", "Intermediate", "DerivedDataCache", "Saved"],
+        )
+        .await?;
+
+        // Unity projects are identified by their version file, and Unreal
+        // ones by their `.uproject` file.
+        let mut projects = HashMap::<PathBuf, (&str, Vec<PathBuf>)>::new();
+        for dir in candidates {
+            let project = match dir.parent() {
+                Some(project) => project.to_path_buf(),
+                None => continue,
+            };
+            let is_library = dir.file_name() == Some(OsStr::new("Library"));
+            let engine = if is_library {
+                if !project.join("ProjectSettings/ProjectVersion.txt").exists() {
+                    continue;
+                }
+                "Unity"
+            } else {
+                let is_unreal = read_dir_paths(&project)
+                    .await
+                    .iter()
+                    .any(|file| file.extension() == Some(OsStr::new("uproject")));
+                if !is_unreal {
+                    continue;
+                }
+                "Unreal"
+            };
+            projects
+                .entry(project)
+                .or_insert((engine, Vec::new()))
+                .1
+                .push(dir);
+        }
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (project, (engine, dirs)) in projects {
+            let size = disk_usage(&dirs).await?;
+            reclaimable += size;
+            content.push(format!(
+                "{} ({engine}, {})",
+                project.display(),
+                human_size(size)
+            ));
+            self.dirs.extend(dirs);
+        }
+        content.sort();
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Game engine project caches"),
+            content: content.join("\n"),
+            fix_available: !self.dirs.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following directories, which the engines regenerate when opening the project:"))?;
+        for dir in &self.dirs {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.dirs {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct DockerLogs {
+    logs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for DockerLogs {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("Docker container logs");
+        if !command_exists("docker") {
+            return Ok(Output {
+                title,
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        let cmd = Command::new("docker")
+            .arg("ps")
+            .arg("--all")
+            .arg("--no-trunc")
+            .arg("--format")
+            .arg("{{.ID}}\t{{.Names}}")
+            .stderr(Stdio::null())
+            .traced_output()
+            .await?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+
+        // The logs are only readable by root
+        let max_size = config.max_log_size * 1024 * 1024;
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (id, name) in stdout.lines().filter_map(|line| line.split_once('\t')) {
+            let log = Path::new(DOCKER_CONTAINERS)
+                .join(id)
+                .join(format!("{id}-json.log"));
+            let size = match fs::metadata(&log).await {
+                Ok(meta) => meta.len(),
+                Err(e) => {
+                    content.push(t!(
+                        "{name}: couldn't read the log size: {error}",
+                        name = name,
+                        error = e
+                    ));
+                    continue;
+                }
+            };
+            content.push(format!("{name}: {}", human_size(size)));
+            if size > max_size {
+                self.logs.push(log);
+                reclaimable += size;
+            }
+        }
+
+        let daemon = fs::read_to_string(DOCKER_DAEMON_CONF)
+            .await
+            .unwrap_or_default();
+        if !daemon.contains("max-size") {
+            content.push(t!(
+                "Log rotation isn't configured in {path}",
+                path = DOCKER_DAEMON_CONF
+            ));
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.logs.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        let sudo = privilege::prefix(config);
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will truncate the following logs with '{command}':",
+                command = format!("{sudo}truncate")
+            )
+        )?;
+        for log in &self.logs {
+            writeln!(out, "* {}", log.display())?;
+        }
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "To avoid this in the future, configure log rotation in {path}:",
+                path = DOCKER_DAEMON_CONF
+            )
+        )?;
+        writeln!(
+            out,
+            r#"  {{ "log-opts": {{ "max-size": "10m", "max-file": "3" }} }}"#
+        )?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, config: &Config) -> Result<FixResult> {
+        let status = privilege::command(config, "truncate")
+            .await?
+            .arg("--size")
+            .arg("0")
+            .arg("--")
+            .args(&self.logs)
+            .traced_status()
+            .await?;
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[derive(Default)]
+pub struct BrowserDownloads {
+    unused: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for BrowserDownloads {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Installed versions of Playwright list the browser revisions they use
+        // in `browsers.json`, and Puppeteer has a `revisions.js` module. They
+        // can also be installed globally, or as Python packages.
+        let mut roots = project_roots(config);
+        roots.push(PathBuf::from(GLOBAL_NODE_MODULES));
+        roots.extend(
+            read_dir_paths("/usr/lib")
+                .await
+                .into_iter()
+                .filter(|path| path.to_string_lossy().starts_with("/usr/lib/python3")),
+        );
+        let manifests = find_files(roots, &["browsers.json", "revisions.js"]).await?;
+        let mut referenced = HashSet::new();
+        for manifest in manifests {
+            let path = manifest.to_string_lossy();
+            // E.g. `node_modules/playwright-core/browsers.json`, or
+            // `site-packages/playwright/driver/package/browsers.json`
+            let playwright = manifest.components().any(|component| {
+                matches!(
+                    component.as_os_str().to_str(),
+                    Some("playwright" | "playwright-core")
+                )
+            });
+            if playwright && path.ends_with("/browsers.json") {
+                let json = fs::read_to_string(&manifest).await.unwrap_or_default();
+                for browser in json.split('{').skip(1) {
+                    let name = json_strings(browser, "name");
+                    let revision = json_strings(browser, "revision");
+                    if let (Some(name), Some(revision)) = (name.first(), revision.first()) {
+                        referenced.insert(format!("{name}-{revision}"));
+                    }
+                }
+            } else if path.ends_with("puppeteer/revisions.js") {
+                // Declared as `chrome: '121.0.6167.85'`
+                let js = fs::read_to_string(&manifest).await.unwrap_or_default();
+                referenced.extend(
+                    js.split('\'')
+                        .filter(|s| {
+                            !s.is_empty() && s.chars().all(|c| c.is_ascii_digit() || c == '.')
+                        })
+                        .map(|version| format!("linux-{version}")),
+                );
+            }
+        }
+
+        // Playwright stores them as `chromium-1091`, and Puppeteer as
+        // `chrome/linux-121.0.6167.85`.
+        let cache = home_dir(config).join(".cache");
+        let mut builds = read_dir_paths(cache.join("ms-playwright")).await;
+        for browser in read_dir_paths(cache.join("puppeteer")).await {
+            builds.extend(read_dir_paths(browser).await);
+        }
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for build in builds {
+            let name = build
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            if name.starts_with('.') || !build.is_dir() {
+                continue;
+            }
+            let size = disk_usage([&build]).await?;
+            if referenced.contains(&name) {
+                content.push(format!("{} ({})", build.display(), human_size(size)));
+            } else {
+                content.push(format!(
+                    "{} ({}, unused)",
+                    build.display(),
+                    human_size(size)
+                ));
+                self.unused.push(build);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Playwright and Puppeteer browsers"),
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following browser builds:")
+        )?;
+        for build in &self.unused {
+            writeln!(out, "* {}", build.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for build in &self.unused {
+            match remove_path(build).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", build.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CypressCache {
+    unused: Vec<PathBuf>,
+}
+impl CypressCache {
+    /// The version of the package installed in `node_modules`, given its
+    /// `package.json`. Only the top-level one counts, since dependencies or
+    /// engines may have a version too.
+    fn installed_version(manifest: &str) -> Option<String> {
+        let manifest = serde_json::from_str::<serde_json::Value>(manifest).ok()?;
+        manifest["version"].as_str().map(ToString::to_string)
+    }
+}
+#[async_trait]
+impl CleanupCommand for CypressCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // The versions used by local projects
+        let mut used = HashSet::new();
+        for deps in find_dirs(project_roots(config), &["node_modules"]).await? {
+            let manifest = fs::read_to_string(deps.join("cypress/package.json"))
+                .await
+                .unwrap_or_default();
+            used.extend(CypressCache::installed_version(&manifest));
+        }
+
+        let cache = user_var(config, "CYPRESS_CACHE_FOLDER")
+            .map_or_else(|| home_dir(config).join(".cache/Cypress"), PathBuf::from);
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for version in read_dir_paths(&cache).await {
+            let name = version
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            let size = disk_usage([&version]).await?;
+            if used.contains(&name) {
+                content.push(format!("{name} ({})", human_size(size)));
+            } else {
+                content.push(format!("{name} ({}, unused)", human_size(size)));
+                self.unused.push(version);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Cypress binary cache"),
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will remove the following versions, like '{command}':",
+                command = "cypress cache prune"
+            )
+        )?;
+        for version in &self.unused {
+            writeln!(out, "* {}", version.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for version in &self.unused {
+            match remove_path(version).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", version.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct DenoCache {
+    deno_dir: PathBuf,
+    active: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for DenoCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        self.deno_dir = user_var(config, "DENO_DIR")
+            .map_or_else(|| home_dir(config).join(".cache/deno"), PathBuf::from);
+
+        // Remote modules are stored in `deps` or in `remote` for newer
+        // versions, and the transpiled code in `gen`.
+        let mut content = Vec::new();
+        let mut clearable = 0;
+        for dir in ["deps", "remote", "gen", "npm"] {
+            let size = disk_usage([self.deno_dir.join(dir)]).await?;
+            if dir != "npm" {
+                clearable += size;
+            }
+            content.push(format!("{dir}: {}", human_size(size)));
+        }
+
+        // The projects whose dependencies should be cached again afterwards
+        for lock in find_files(project_roots(config), &["deno.lock"]).await? {
+            if modified(&lock).await.map(days_since).unwrap_or(0) <= config.max_age {
+                if let Some(project) = lock.parent() {
+                    self.active.push(project.to_path_buf());
+                }
+            }
+        }
+        content.push(format!("{} active projects", self.active.len()));
+
+        Ok(Output {
+            title: t!("Deno cache"),
+            content: content.join("\n"),
+            fix_available: clearable > 0,
+            reclaimable_bytes: Some(clearable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the remote modules and codegen caches in {dir}, and then run '{command}' to restore the pinned dependencies of:", dir = self.deno_dir.display(), command = "deno install"))?;
+        for project in &self.active {
+            writeln!(out, "* {}", project.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in ["deps", "remote", "gen"] {
+            let path = self.deno_dir.join(dir);
+            match remove_path(&path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                    fixed.add_failure(format!("couldn't remove {}: {e}", path.display()));
+                }
+                Err(_) => {}
+            }
+        }
+
+        for project in &self.active {
+            let status = Command::new("deno")
+                .arg("install")
+                .current_dir(project)
+                .traced_status()
+                .await?;
+            fixed.add(&FixResult::status(status));
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct BunCache {
+    bunx: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for BunCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let cache = user_var(config, "BUN_INSTALL_CACHE_DIR").map_or_else(
+            || home_dir(config).join(".bun/install/cache"),
+            PathBuf::from,
+        );
+        let cache_size = disk_usage([&cache]).await?;
+
+        // Packages run with `bunx` are installed to `/tmp/bunx-<uid>-<pkg>`,
+        // where only the ones of the user being checked, who owns the home, can
+        // be removed.
+        let uid = fs::metadata(home_dir(config)).await?.uid();
+        let prefix = format!("bunx-{uid}-");
+        let tmp = env::temp_dir();
+        for path in read_dir_paths(&tmp).await {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            if name.starts_with(&prefix) {
+                self.bunx.push(path);
+            }
+        }
+        let bunx_size = disk_usage(&self.bunx).await?;
+
+        Ok(Output {
+            title: t!("Bun cache"),
+            content: t!(
+                "{size} in {dir}\n{bunx_size} in bunx caches",
+                size = human_size(cache_size),
+                dir = cache.display(),
+                bunx_size = human_size(bunx_size)
+            ),
+            fix_available: cache_size > 0 || !self.bunx.is_empty(),
+            reclaimable_bytes: Some(cache_size + bunx_size),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix will run the command '{command}' and remove:",
+                command = "bun pm cache rm"
+            )
+        )?;
+        for path in &self.bunx {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        Command::new("bun")
+            .arg("pm")
+            .arg("cache")
+            .arg("rm")
+            .traced_status()
+            .await?;
+
+        for path in &self.bunx {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct PubCache {
+    pub_cache_size: u64,
+    /// Artifact caches of the Flutter SDKs not used by any project
+    unused_artifacts: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for PubCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let pub_cache =
+            user_var(config, "PUB_CACHE").map_or_else(|| home.join(".pub-cache"), PathBuf::from);
+        self.pub_cache_size = disk_usage([&pub_cache]).await?;
+        let mut content = vec![format!(
+            "{} in {}",
+            human_size(self.pub_cache_size),
+            pub_cache.display()
+        )];
+
+        // The SDK in `$PATH`, whose engine artifacts are in `bin/cache`
+        if let Some(sdk) = find_program("flutter")
+            .and_then(|bin| std::fs::canonicalize(bin).ok())
+            .and_then(|bin| Some(bin.parent()?.parent()?.to_path_buf()))
+        {
+            let size = disk_usage([sdk.join("bin/cache")]).await?;
+            content.push(format!(
+                "{} in {} artifacts",
+                human_size(size),
+                sdk.display()
+            ));
+        }
+
+        // SDKs for different channels or versions are usually managed with
+        // FVM, and projects pin them with `.fvmrc` or `.fvm/fvm_config.json`.
+        let mut reclaimable = self.pub_cache_size;
+        let mut used = HashSet::new();
+        for pin in find_files(project_roots(config), &[".fvmrc", "fvm_config.json"]).await? {
+            let json = fs::read_to_string(pin).await.unwrap_or_default();
+            used.extend(
+                json_strings(&json, "flutter")
+                    .into_iter()
+                    .map(ToString::to_string),
+            );
+            used.extend(
+                json_strings(&json, "flutterSdkVersion")
+                    .into_iter()
+                    .map(ToString::to_string),
+            );
+        }
+        for fvm in [home.join("fvm"), home.join(".fvm")] {
+            // The global version is always in use
+            if let Ok(default) = fs::read_link(fvm.join("default")).await {
+                used.extend(
+                    default
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned()),
+                );
+            }
+            for sdk in read_dir_paths(fvm.join("versions")).await {
+                let name = sdk
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                let size = disk_usage([sdk.join("bin/cache")]).await?;
+                if used.contains(&name) || size == 0 {
+                    continue;
+                }
+                content.push(format!(
+                    "FVM {name} is unused, {} in artifacts",
+                    human_size(size)
+                ));
+                self.unused_artifacts.push(sdk.join("bin/cache"));
+                reclaimable += size;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Flutter and Dart pub cache"),
+            content: content.join("\n"),
+            fix_available: self.pub_cache_size > 0 || !self.unused_artifacts.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        if self.pub_cache_size > 0 {
+            let command = "dart pub cache clean --force";
+            writeln!(
+                out,
+                "{}",
+                t!(
+                    "This fix will run the command '{command}'",
+                    command = command
+                )
+            )?;
+        }
+        if !self.unused_artifacts.is_empty() {
+            writeln!(
+                out,
+                "{}",
+                t!("This fix will remove the artifacts of the unused SDKs:")
+            )?;
+            for path in &self.unused_artifacts {
+                writeln!(out, "* {}", path.display())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        if self.pub_cache_size > 0 {
+            Command::new("dart")
+                .arg("pub")
+                .arg("cache")
+                .arg("clean")
+                .arg("--force")
+                .traced_status()
+                .await?;
+        }
+
+        // They're downloaded again the next time the SDK is used
+        for path in &self.unused_artifacts {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct RubyBundles {
+    stale: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for RubyBundles {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let candidates = find_dirs(project_roots(config), &["bundle", ".bundle"]).await?;
+        // Gems are installed to `vendor/bundle`
+        let bundles = candidates
+            .into_iter()
+            .filter(|dir| {
+                dir.file_name() == Some(OsStr::new(".bundle"))
+                    || dir.parent().and_then(Path::file_name) == Some(OsStr::new("vendor"))
+            })
+            .collect();
+        let projects = artifacts_by_project(bundles, &["Gemfile"], 2).await?;
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (project, size, dirs) in projects {
+            // Projects are touched when their dependencies change or files are
+            // added to them
+            let last = modified(project.join("Gemfile.lock"))
+                .await
+                .max(modified(&project).await);
+            let days = last.map(days_since).unwrap_or(0);
+            content.push(format!(
+                "{} ({}, untouched for {days} days)",
+                project.display(),
+                human_size(size)
+            ));
+            if days > config.max_age {
+                self.stale.extend(dirs);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Vendored Ruby bundles"),
+            content: content.join("\n"),
+            fix_available: !self.stale.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for dir in &self.stale {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.stale {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct ElixirCaches {
+    stale: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for ElixirCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let hex = user_var(config, "HEX_HOME").map_or_else(|| home.join(".hex"), PathBuf::from);
+        let mix = user_var(config, "MIX_HOME").map_or_else(|| home.join(".mix"), PathBuf::from);
+        let mut content = vec![
+            format!(
+                "{} in {}",
+                human_size(disk_usage([&hex]).await?),
+                hex.display()
+            ),
+            format!(
+                "{} in archives",
+                human_size(disk_usage([mix.join("archives")]).await?)
+            ),
+        ];
+
+        // Packages are cached as `<name>-<version>.tar`, and only the most
+        // recent version of each is kept.
+        let mut by_name = HashMap::<String, Vec<_>>::new();
+        for tarball in read_dir_paths(hex.join("packages/hexpm")).await {
+            let file_name = tarball
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+            if let Some((name, _version)) = file_name.trim_end_matches(".tar").rsplit_once('-') {
+                let time = modified(&tarball).await;
+                by_name
+                    .entry(name.to_string())
+                    .or_default()
+                    .push((time, tarball));
+            }
+        }
+        let mut old_packages = Vec::new();
+        for (_, mut versions) in by_name {
+            versions.sort();
+            versions.pop();
+            old_packages.extend(versions.into_iter().map(|(_, path)| path));
+        }
+        let mut reclaimable = disk_usage(&old_packages).await?;
+        content.push(format!(
+            "{} old package versions ({})",
+            old_packages.len(),
+            human_size(reclaimable)
+        ));
+        self.stale.extend(old_packages);
+
+        // Builds of projects that haven't been compiled in a while
+        let builds = find_dirs(project_roots(config), &["_build"]).await?;
+        for (project, size, dirs) in artifacts_by_project(builds, &["mix.exs"], 1).await? {
+            let deps = disk_usage([project.join("deps")]).await?;
+            let days = modified(project.join("_build"))
+                .await
+                .map(days_since)
+                .unwrap_or(0);
+            content.push(format!(
+                "{}: _build {}, deps {}, built {days} days ago",
+                project.display(),
+                human_size(size),
+                human_size(deps)
+            ));
+            if days > config.max_age {
+                self.stale.extend(dirs);
+                reclaimable += size;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Elixir mix and hex caches"),
+            content: content.join("\n"),
+            fix_available: !self.stale.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following files and directories:")
+        )?;
+        for path in &self.stale {
+            writeln!(out, "* {}", path.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for path in &self.stale {
+            match remove_path(path).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", path.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct ZigCache {
+    global: Option<PathBuf>,
+    stale: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for ZigCache {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let global = user_var(config, "ZIG_GLOBAL_CACHE_DIR")
+            .map_or_else(|| home_dir(config).join(".cache/zig"), PathBuf::from);
+        let global_size = disk_usage([&global]).await?;
+        let mut content = vec![format!(
+            "{} in {}",
+            human_size(global_size),
+            global.display()
+        )];
+        let mut reclaimable = global_size;
+        if global_size > 0 {
+            self.global = Some(global);
+        }
+
+        // Older versions use `zig-cache` instead of `.zig-cache`
+        let caches = find_dirs(
+            project_roots(config),
+            &["zig-cache", ".zig-cache", "zig-out"],
+        )
+        .await?;
+        for (project, size, dirs) in artifacts_by_project(caches, &["build.zig"], 1).await? {
+            let mut last = None;
+            for dir in &dirs {
+                last = last.max(modified(dir).await);
+            }
+            let days = last.map(days_since).unwrap_or(0);
+            content.push(format!(
+                "{} ({}, built {days} days ago)",
+                project.display(),
+                human_size(size)
+            ));
+            if days > config.max_age {
+                self.stale.extend(dirs);
+                reclaimable += size;
+            }
+        }
+
+        Ok(Output {
+            title: t!("Zig caches"),
+            content: content.join("\n"),
+            fix_available: self.global.is_some() || !self.stale.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for dir in self.global.iter().chain(&self.stale) {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in self.global.iter().chain(&self.stale) {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct OpamSwitches {
+    unused: Vec<String>,
+}
+#[async_trait]
+impl CleanupCommand for OpamSwitches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let title = t!("opam switches");
+        if !command_exists("opam") {
+            return Ok(Output {
+                title,
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        let root = user_var(config, "OPAMROOT")
+            .map_or_else(|| home_dir(config).join(".opam"), PathBuf::from);
+        let cmd = Command::new("opam")
+            .arg("switch")
+            .arg("show")
+            .traced_output()
+            .await?;
+        let current = String::from_utf8(cmd.stdout)?.trim().to_string();
+
+        // Projects use a switch through an `_opam` directory, which is either
+        // a local switch or a link to a global one.
+        let cmd = Command::new("find")
+            .args(project_roots(config))
+            .arg("-name")
+            .arg("_opam")
+            .arg("-prune")
+            .stderr(Stdio::null())
+            .traced_output()
+            .await?;
+        let mut projects = HashMap::<PathBuf, Vec<PathBuf>>::new();
+        for link in String::from_utf8(cmd.stdout)?.lines().map(PathBuf::from) {
+            let target = std::fs::canonicalize(&link).unwrap_or_else(|_| link.clone());
+            if let Some(project) = link.parent() {
+                projects
+                    .entry(target)
+                    .or_default()
+                    .push(project.to_path_buf());
+            }
+        }
+
+        // Local switches are listed by the path of their project
+        let cmd = Command::new("opam")
+            .arg("switch")
+            .arg("list")
+            .arg("--short")
+            .traced_output()
+            .await?;
+        let mut content = Vec::new();
+        let download_cache = disk_usage([root.join("download-cache")]).await?;
+        let mut reclaimable = download_cache;
+        for switch in String::from_utf8(cmd.stdout)?.lines() {
+            let prefix = if switch.starts_with('/') {
+                Path::new(switch).join("_opam")
+            } else {
+                root.join(switch)
+            };
+            let prefix = std::fs::canonicalize(&prefix).unwrap_or(prefix);
+            let size = disk_usage([&prefix]).await?;
+
+            // Switches are used when the compiler runs, and modified when
+            // packages are installed.
+            let last = accessed(prefix.join("bin/ocaml"))
+                .await
+                .max(modified(prefix.join(".opam-switch")).await);
+            let days = last.map(days_since).unwrap_or(0);
+            let pinned = projects
+                .get(&prefix)
+                .map(|projects| {
+                    projects
+                        .iter()
+                        .map(|p| p.display().to_string())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                })
+                .unwrap_or_else(|| "no projects".to_string());
+            content.push(format!(
+                "{switch} ({}, used {days} days ago, {pinned})",
+                human_size(size)
+            ));
+            if switch != current && days > config.max_age {
+                self.unused.push(switch.to_string());
+                reclaimable += size;
+            }
+        }
+        content.push(format!("Download cache: {}", human_size(download_cache)));
+
+        Ok(Output {
+            title,
+            content: content.join("\n"),
+            fix_available: !self.unused.is_empty() || download_cache > 0,
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will run the commands:"))?;
+        for switch in &self.unused {
+            writeln!(out, "  opam switch remove --yes {switch}")?;
+        }
+        writeln!(out, "  opam clean --download-cache")?;
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for switch in &self.unused {
+            let status = Command::new("opam")
+                .arg("switch")
+                .arg("remove")
+                .arg("--yes")
+                .arg(switch)
+                .traced_status()
+                .await?;
+            if status.success() {
+                fixed.removed += 1;
+            } else {
+                fixed.add_failure(format!(
+                    "couldn't remove the switch {switch}: 'opam switch remove' exited with {status}"
+                ));
+            }
+        }
+
+        let status = Command::new("opam")
+            .arg("clean")
+            .arg("--download-cache")
+            .traced_status()
+            .await?;
+        fixed.status = Some(status);
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct CppPackageCaches {
+    conan: bool,
+    vcpkg_dirs: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for CppPackageCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+
+        let conan =
+            user_var(config, "CONAN_HOME").map_or_else(|| home.join(".conan2"), PathBuf::from);
+        let conan_size = disk_usage([&conan]).await?;
+        if conan_size > 0 {
+            content.push(format!(
+                "Conan: {} in {}",
+                human_size(conan_size),
+                conan.display()
+            ));
+            self.conan = command_exists("conan");
+            if self.conan {
+                reclaimable += conan_size;
+            }
+        }
+
+        let vcpkg =
+            user_var(config, "VCPKG_ROOT").map_or_else(|| home.join("vcpkg"), PathBuf::from);
+        // The triplets in use are the ones installed, e.g. `installed/x64-linux`
+        let installed = read_dir_paths(vcpkg.join("installed"))
+            .await
+            .into_iter()
+            .filter_map(|path| Some(path.file_name()?.to_string_lossy().into_owned()))
+            .collect::<HashSet<_>>();
+        // Built packages are named `<port>_<triplet>`
+        let unused_triplets = read_dir_paths(vcpkg.join("packages"))
+            .await
+            .into_iter()
+            .filter_map(|path| {
+                let name = path.file_name()?.to_string_lossy().into_owned();
+                let (_, triplet) = name.rsplit_once('_')?;
+                (!installed.contains(triplet)).then(|| triplet.to_string())
+            })
+            .collect::<HashSet<_>>();
+        for dir in ["buildtrees", "packages", "downloads"] {
+            let path = vcpkg.join(dir);
+            let size = disk_usage([&path]).await?;
+            if size > 0 {
+                content.push(format!("vcpkg {dir}: {}", human_size(size)));
+                self.vcpkg_dirs.push(path);
+                reclaimable += size;
+            }
+        }
+        if !unused_triplets.is_empty() {
+            let mut unused_triplets = unused_triplets.into_iter().collect::<Vec<_>>();
+            unused_triplets.sort();
+            content.push(format!(
+                "vcpkg has packages for unused triplets: {}",
+                unused_triplets.join(", ")
+            ));
+        }
+
+        // Binary caching is enabled by default
+        let archives = user_var(config, "VCPKG_DEFAULT_BINARY_CACHE")
+            .map_or_else(|| home.join(".cache/vcpkg/archives"), PathBuf::from);
+        let size = disk_usage([&archives]).await?;
+        if size > 0 {
+            content.push(format!("vcpkg binary cache: {}", human_size(size)));
+            self.vcpkg_dirs.push(archives);
+            reclaimable += size;
+        }
+
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Conan and vcpkg caches"),
+            content: content.join("\n"),
+            fix_available: self.conan || !self.vcpkg_dirs.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        if self.conan {
+            writeln!(
+                out,
+                "{}",
+                t!(
+                    "This fix will run the command '{command}'",
+                    command = "conan cache clean"
+                )
+            )?;
+        }
+        if !self.vcpkg_dirs.is_empty() {
+            writeln!(
+                out,
+                "{}",
+                t!("This fix will remove the following vcpkg directories:")
+            )?;
+            for dir in &self.vcpkg_dirs {
+                writeln!(out, "* {}", dir.display())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        if self.conan {
+            Command::new("conan")
+                .arg("cache")
+                .arg("clean")
+                .traced_status()
+                .await?;
+        }
+
+        for dir in &self.vcpkg_dirs {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct ScalaCaches {
+    dirs: Vec<PathBuf>,
+    /// The artifact version directories not resolved in `max_age` days
+    old: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for ScalaCaches {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        let home = home_dir(config);
+        let coursier = user_var(config, "COURSIER_CACHE")
+            .map_or_else(|| home.join(".cache/coursier"), PathBuf::from);
+        // The rest of `~/.sbt` has the user's settings, plugins and
+        // credentials
+        self.dirs = vec![
+            home.join(".ivy2/cache"),
+            home.join(".sbt/boot"),
+            home.join(".sbt/zinc"),
+            coursier,
+        ];
+
+        let mut content = Vec::new();
+        for dir in &self.dirs {
+            let size = disk_usage([dir]).await?;
+            content.push(format!("{} ({})", dir.display(), human_size(size)));
+        }
+
+        // Artifacts are read by the build tools every time they're resolved,
+        // so the ones that haven't been accessed in a while are from old
+        // versions or projects. That's unknown with `noatime`, where all of
+        // them would look unused.
+        for dir in self.dirs.iter().filter(|dir| dir.is_dir()) {
+            if !atime_updated(dir).await {
+                content.push(format!(
+                    "{} is mounted with noatime, so the unused artifacts can't be told apart",
+                    dir.display()
+                ));
+                return Ok(Output {
+                    title: t!("Scala sbt, Ivy and Coursier caches"),
+                    content: content.join("\n"),
+                    fix_available: false,
+                    reclaimable_bytes: None,
+                });
+            }
+        }
+
+        // Each version of an artifact is in its own directory, e.g.
+        // `<group>/<artifact>/<version>/` in Coursier, which is only removed
+        // if none of its files have been accessed in a while. Only the
+        // directories without subdirectories are considered.
+        let cmd = Command::new("find")
+            .args(self.dirs.iter().filter(|dir| dir.is_dir()))
+            .arg("-mindepth")
+            .arg("1")
+            .arg("(")
+            .arg("-type")
+            .arg("f")
+            .arg("-printf")
+            .arg("f %A@ %s %h\\n")
+            .arg(")")
+            .arg("-o")
+            .arg("(")
+            .arg("-type")
+            .arg("d")
+            .arg("-printf")
+            .arg("d %h\\n")
+            .arg(")")
+            .stderr(Stdio::null())
+            .traced_output()
+            .await?;
+        let stdout = String::from_utf8(cmd.stdout)?;
+        // The last access and size of the files in each directory
+        let mut versions = HashMap::<&str, (f64, u64)>::new();
+        let mut parents = HashSet::new();
+        for line in stdout.lines() {
+            if let Some(parent) = line.strip_prefix("d ") {
+                parents.insert(parent);
+                continue;
+            }
+            let mut fields = line.splitn(4, ' ').skip(1);
+            let (Some(accessed), Some(size), Some(dir)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let version = versions.entry(dir).or_default();
+            version.0 = version.0.max(accessed.parse().unwrap_or(f64::MAX));
+            version.1 += size.parse::<u64>().unwrap_or(0);
+        }
+
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs_f64())
+            .unwrap_or(0.0);
+        let mut old_size = 0;
+        for (dir, (accessed, size)) in versions {
+            let days = ((now - accessed) / 86400.0).max(0.0) as u64;
+            if days > config.max_age && !parents.contains(dir) {
+                old_size += size;
+                self.old.push(PathBuf::from(dir));
+            }
+        }
+        self.old.sort();
+        content.push(format!(
+            "{} artifact versions not resolved in {} days ({})",
+            self.old.len(),
+            config.max_age,
+            human_size(old_size)
+        ));
+
+        Ok(Output {
+            title: t!("Scala sbt, Ivy and Coursier caches"),
+            content: content.join("\n"),
+            fix_available: !self.old.is_empty(),
+            reclaimable_bytes: Some(old_size),
+        })
+    }
+
+    fn show_fix(&self, config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following artifact versions, not resolved in {days} days, and then the directories left empty:", days = config.max_age))?;
+        for dir in &self.old {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.old {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Command::new("find")
+            .args(self.dirs.iter().filter(|dir| dir.is_dir()))
+            .arg("-mindepth")
+            .arg("1")
+            .arg("-type")
+            .arg("d")
+            .arg("-empty")
+            .arg("-delete")
+            .stderr(Stdio::null())
+            .traced_status()
+            .await?;
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct RLibraries {
+    removable: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for RLibraries {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Without R, it can't be told which libraries are still used
+        if !command_exists("R") {
+            return Ok(Output {
+                title: t!("R user libraries"),
+                content: t!("(not installed)"),
+                fix_available: false,
+                reclaimable_bytes: None,
+            });
+        }
+
+        // Outputs `R version 4.3.2 (2023-10-31) -- "Eye Holes"`, and the
+        // libraries are per minor version.
+        let cmd = Command::new("R").arg("--version").traced_output().await?;
+        let version = String::from_utf8(cmd.stdout)?
+            .split_whitespace()
+            .nth(2)
+            .and_then(|version| version.rsplit_once('.'))
+            .map(|(minor, _patch)| minor.to_string())
+            .context("couldn't read the version of R")?;
+
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for platform in read_dir_paths(home_dir(config).join("R")).await {
+            for library in read_dir_paths(&platform).await {
+                let name = library
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned();
+                let size = disk_usage([&library]).await?;
+                if name == version {
+                    content.push(format!("{} ({})", library.display(), human_size(size)));
+                } else {
+                    content.push(format!(
+                        "{} ({}, R {name} not installed)",
+                        library.display(),
+                        human_size(size)
+                    ));
+                    self.removable.push(library);
+                    reclaimable += size;
+                }
+            }
+        }
+
+        // Packages are downloaded to the temporary directory of each session
+        for tmp in read_dir_paths(env::temp_dir()).await {
+            let name = tmp.file_name().unwrap_or_default().to_string_lossy();
+            let downloads = tmp.join("downloaded_packages");
+            if name.starts_with("Rtmp") && downloads.is_dir() {
+                let size = disk_usage([&downloads]).await?;
+                content.push(format!("{} ({})", downloads.display(), human_size(size)));
+                self.removable.push(downloads);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("R user libraries"),
+            content: content.join("\n"),
+            fix_available: !self.removable.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(
+            out,
+            "{}",
+            t!("This fix will remove the following directories:")
+        )?;
+        for dir in &self.removable {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.removable {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+#[derive(Default)]
+pub struct GodotImports {
+    stale: Vec<PathBuf>,
+}
+#[async_trait]
+impl CleanupCommand for GodotImports {
+    async fn check(&mut self, config: &Config) -> Result<Output> {
+        // Godot 4 stores the imported assets in `.godot/imported`, and
+        // Godot 3 in `.import`
+        let dirs = find_dirs(project_roots(config), &[".godot", ".import"]).await?;
+        let mut content = Vec::new();
+        let mut reclaimable = 0;
+        for (project, _, dirs) in artifacts_by_project(dirs, &["project.godot"], 1).await? {
+            // The editor writes its state on every open
+            let mut last = None;
+            for dir in &dirs {
+                last = last.max(modified(dir).await);
+            }
+            let days = last.map(days_since).unwrap_or(0);
+
+            let imports = dirs
+                .into_iter()
+                .map(|dir| {
+                    if dir.ends_with(".godot") {
+                        dir.join("imported")
+                    } else {
+                        dir
+                    }
+                })
+                .filter(|dir| dir.is_dir())
+                .collect::<Vec<_>>();
+            let size = disk_usage(&imports).await?;
+            content.push(format!(
+                "{} ({}, opened {days} days ago)",
+                project.display(),
+                human_size(size)
+            ));
+            if days > config.max_age {
+                self.stale.extend(imports);
+                reclaimable += size;
+            }
+        }
+        if content.is_empty() {
+            content.push(t!("(none)"));
+        }
+
+        Ok(Output {
+            title: t!("Godot project import caches"),
+            content: content.join("\n"),
+            fix_available: !self.stale.is_empty(),
+            reclaimable_bytes: Some(reclaimable),
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        writeln!(out, "{}", t!("This fix will remove the following directories, which the editor regenerates when opening the project:"))?;
+        for dir in &self.stale {
+            writeln!(out, "* {}", dir.display())?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let mut fixed = FixResult::default();
+        for dir in &self.stale {
+            match remove_path(dir).await {
+                Ok(bytes) => fixed.add_removed(bytes),
+                Err(e) => fixed.add_failure(format!("couldn't remove {}: {e}", dir.display())),
+            }
+        }
+
+        Ok(fixed)
+    }
+}
+
+/// A command defined by the user in the configuration file, which runs shell
+/// commands for both the check and the fix.
+pub struct Custom {
+    def: CustomCheck,
+}
+
+impl Custom {
+    pub fn new(def: CustomCheck) -> Self {
+        Custom { def }
+    }
+
+    /// Whether the output of the check means that the fix should be applied.
+    /// By default that's the case when it isn't empty.
+    fn needs_fix(&self, output: &str) -> Result<bool> {
+        if self.def.fix.is_none() || output.is_empty() {
+            return Ok(false);
+        }
+
+        if let Some(pattern) = &self.def.pattern {
+            let re = Regex::new(pattern)
+                .with_context(|| format!("invalid pattern for '{}'", self.def.id))?;
+            if !re.is_match(output) {
+                return Ok(false);
+            }
+        }
+        if let Some(threshold) = self.def.threshold {
+            // The first number found in the output is compared
+            let value = output
+                .split(|c: char| c.is_whitespace() || c == ',' || c == ':')
+                .find_map(|word| word.parse::<f64>().ok());
+            if !value.is_some_and(|value| value > threshold) {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl CleanupCommand for Custom {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let cmd = Command::new("sh")
+            .arg("-c")
+            .arg(&self.def.check)
+            .traced_output()
+            .await?;
+        if !cmd.status.success() {
+            let stderr = String::from_utf8_lossy(&cmd.stderr);
+            let mut msg = format!("'{}' exited with {}", self.def.check, cmd.status);
+            let stderr = stderr.trim();
+            if !stderr.is_empty() {
+                msg.push_str(&format!(": {stderr}"));
+            }
+            bail!(msg);
+        }
+        let stdout = String::from_utf8(cmd.stdout)?;
+        let stdout = stdout.trim();
+
+        Ok(Output {
+            title: self.def.description(),
+            content: if stdout.is_empty() {
+                t!("(none)")
+            } else {
+                stdout.to_string()
+            },
+            fix_available: self.needs_fix(stdout)?,
+            reclaimable_bytes: None,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> io::Result<()> {
+        if let Some(fix) = &self.def.fix {
+            writeln!(
+                out,
+                "{}",
+                t!("This fix will run the command '{command}'", command = fix)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        let fix = match &self.def.fix {
+            Some(fix) => fix,
+            None => return Ok(FixResult::default()),
+        };
+
+        let status = Command::new("sh")
+            .arg("-c")
+            .arg(fix)
+            .traced_status()
+            .await?;
+        if !status.success() {
+            bail!("'{fix}' exited with {status}");
+        }
+
+        Ok(FixResult::status(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Options;
+
+    #[test]
+    fn parse_binary_sizes() {
+        assert_eq!(parse_size("1.5G"), Some(1536 * 1024 * 1024));
+        assert_eq!(parse_size("120.50 MiB"), Some(126_353_408));
+        assert_eq!(parse_size("100M"), Some(100 * 1024 * 1024));
+        assert_eq!(parse_size("4k"), Some(4096));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("0 B"), Some(0));
+    }
+
+    #[test]
+    fn parse_decimal_sizes() {
+        assert_eq!(parse_size("3.8 GB"), Some(3_800_000_000));
+        assert_eq!(parse_size("12kB"), Some(12_000));
+        assert_eq!(parse_size("1 TB"), Some(1_000_000_000_000));
+    }
+
+    #[test]
+    fn parse_invalid_sizes() {
+        assert_eq!(parse_size(""), None);
+        assert_eq!(parse_size("GiB"), None);
+        assert_eq!(parse_size("1.5 XB"), None);
+        assert!("tiny".parse::<Size>().is_err());
+    }
+
+    #[test]
+    fn format_sizes() {
+        assert_eq!(format_size(512, ByteFormat::Iec), "512 B");
+        assert_eq!(format_size(1536, ByteFormat::Iec), "1.5 KiB");
+        assert_eq!(format_size(1536 * 1024 * 1024, ByteFormat::Iec), "1.5 GiB");
+        assert_eq!(format_size(1_500_000, ByteFormat::Si), "1.5 MB");
+        assert_eq!(format_size(1_572_864, ByteFormat::Raw), "1572864 B");
+    }
+
+    #[test]
+    fn parse_byte_formats() {
+        for format in [ByteFormat::Raw, ByteFormat::Iec, ByteFormat::Si] {
+            assert_eq!(format.to_string().parse(), Ok(format));
+        }
+        assert!("bytes".parse::<ByteFormat>().is_err());
+    }
+
+    #[test]
+    fn paccache_runs() {
+        let mut config = Config::from(Options::default());
+        assert_eq!(Paccache::runs(&config), [["-k3"]]);
+
+        config.keep = 1;
+        config.purge_uninstalled = true;
+        assert_eq!(Paccache::runs(&config), [vec!["-k1"], vec!["-u", "-k0"]]);
+    }
+
+    #[test]
+    fn paccache_candidates() {
+        let stdout = "\
+/var/cache/pacman/pkg/foo-1.0-1-x86_64.pkg.tar.zst
+bar-2.0-1-any.pkg.tar.zst
+
+==> finished dry run: 2 candidates (disk space saved: 1.50 MiB)
+";
+        let candidates = Paccache::candidates(stdout).collect::<Vec<_>>();
+        assert_eq!(
+            candidates,
+            [
+                PathBuf::from("/var/cache/pacman/pkg/foo-1.0-1-x86_64.pkg.tar.zst"),
+                PathBuf::from("/var/cache/pacman/pkg/bar-2.0-1-any.pkg.tar.zst"),
+            ]
+        );
+
+        let stdout = "==> no candidate packages found for pruning\n";
+        assert_eq!(Paccache::candidates(stdout).count(), 0);
+    }
+
+    #[test]
+    fn mork_expunged() {
+        let msf = r#"// <!-- <mdb:mork:z v="1.4"/> -->
+< <(a=c)> // (f=iso-8859-1)
+  (8A=expungedBytes)(8B=folderSize)>
+<(80=0)>
+{1:^80 {(k^BF:c)(s=9)} [1:^82(^8A=1f4e)(^8B=10000)]}
+@$${2{@
+[1:^82(^8A=2710)]
+@$$}2}@"#;
+        // The last value is the current one
+        assert_eq!(mork_expunged_bytes(msf), Some(10_000));
+
+        let msf = "< <(a=c)> (8A=expungedBytes)> [1:^82(^8A=1f4e)]";
+        assert_eq!(mork_expunged_bytes(msf), Some(0x1f4e));
+    }
+
+    #[test]
+    fn mork_expunged_malformed() {
+        // Nothing expunged yet
+        assert_eq!(mork_expunged_bytes("< <(a=c)> (8B=folderSize)>"), None);
+        assert_eq!(mork_expunged_bytes("(8A=expungedBytes)"), None);
+        // Truncated or invalid values
+        assert_eq!(mork_expunged_bytes("(8A=expungedBytes) [1:(^8A=1f4e"), None);
+        assert_eq!(
+            mork_expunged_bytes("(8A=expungedBytes) [1:(^8A=xyz)]"),
+            None
+        );
+        assert_eq!(mork_expunged_bytes("8A=expungedBytes) (^8A=1f4e)"), None);
+        assert_eq!(mork_expunged_bytes(""), None);
+    }
+
+    #[test]
+    fn xml_attrs() {
+        let tag = r#"<folder id="abcd-1234" path="/home/mario/Sync" type='sendreceive'>"#;
+        assert_eq!(xml_attr(tag, "id"), Some("abcd-1234"));
+        assert_eq!(xml_attr(tag, "path"), Some("/home/mario/Sync"));
+        assert_eq!(xml_attr(tag, "type"), Some("sendreceive"));
+        assert_eq!(xml_attr(tag, "label"), None);
+        // Without the opening bracket
+        assert_eq!(
+            xml_attr(r#"param key="maxAge" val="0""#, "key"),
+            Some("maxAge")
+        );
+        assert_eq!(xml_attr(r#"param key="maxAge" val="0""#, "val"), Some("0"));
+    }
+
+    #[test]
+    fn xml_attrs_by_whole_name() {
+        let tag = r#"<folder folderpath="/a" pathx="/b" path="/c">"#;
+        assert_eq!(xml_attr(tag, "path"), Some("/c"));
+        assert_eq!(xml_attr(r#"<folder paths="/a">"#, "path"), None);
+        assert_eq!(xml_attr(r#"<folder xpath="/a">"#, "path"), None);
+        // Unquoted or unterminated values
+        assert_eq!(xml_attr("<folder path=/a>", "path"), None);
+        assert_eq!(xml_attr(r#"<folder path="/a>"#, "path"), None);
+    }
+
+    #[test]
+    fn ini_values() {
+        let conf = "\
+# num_logs = 9
+log_file = /var/log/audit/audit.log
+num_logs=5
+  max_log_file   =  8  
+
+[Desktop Entry]
+Exec=/usr/bin/foo %U
+Name=Foo
+";
+        assert_eq!(ini_value(conf, "num_logs"), Some("5"));
+        assert_eq!(ini_value(conf, "max_log_file"), Some("8"));
+        // The section is ignored
+        assert_eq!(ini_value(conf, "Exec"), Some("/usr/bin/foo %U"));
+        assert_eq!(ini_value(conf, "max_log"), None);
+        assert_eq!(ini_value(conf, "space_left"), None);
+        assert_eq!(ini_value("", "num_logs"), None);
+    }
+
+    #[test]
+    fn find_programs() {
+        assert_eq!(
+            find_program("/bin/sh -c true"),
+            Some(PathBuf::from("/bin/sh"))
+        );
+        assert_eq!(
+            find_program(r#"env FOO=1 "/bin/sh" --bar"#),
+            Some(PathBuf::from("/bin/sh"))
+        );
+        assert!(find_program("sh -c true").is_some_and(|path| path.ends_with("sh")));
+        assert_eq!(find_program("/nonexistent/foo --bar"), None);
+        assert_eq!(find_program("arch-clean-nonexistent"), None);
+        assert_eq!(find_program("env FOO=1"), None);
+        assert_eq!(find_program(""), None);
+    }
+
+    #[test]
+    fn cypress_installed_version() {
+        let manifest = r#"{
+            "name": "cypress",
+            "engines": { "node": ">=18.0.0", "version": "1" },
+            "dependencies": { "@cypress/request": { "version": "3.0.1" } },
+            "version": "13.6.2"
+        }"#;
+        assert_eq!(
+            CypressCache::installed_version(manifest),
+            Some("13.6.2".to_string())
+        );
+        assert_eq!(
+            CypressCache::installed_version(r#"{"name": "cypress"}"#),
+            None
+        );
+        assert_eq!(CypressCache::installed_version(""), None);
+    }
+
+    #[test]
+    fn appimage_names() {
+        for (file_name, name) in [
+            ("Obsidian-1.4.16-x86_64.AppImage", "obsidian"),
+            ("Obsidian-1.5.3.AppImage", "obsidian"),
+            ("balenaEtcher-v1.18.11-x64.AppImage", "balenaetcher"),
+            ("FreeCAD_0.21.2-Linux-x86_64.AppImage", "freecad"),
+            ("cool-retro-term-1.2.0-x86_64.AppImage", "cool-retro-term"),
+            ("LM_Studio-0.2.8-beta-v1.AppImage", "lm-studio"),
+            ("Krita-x86_64.appimage", "krita"),
+            ("Some-App-aarch64.APPIMAGE", "some-app"),
+            ("Joplin.AppImage", "joplin"),
+        ] {
+            assert_eq!(appimage_name(file_name), name, "{file_name}");
+        }
+    }
+
+    #[test]
+    fn dkms_status() {
+        for (line, parsed) in [
+            (
+                "nvidia/545.29.06, 6.6.8-arch1-1, x86_64: installed",
+                Some(("nvidia/545.29.06", "6.6.8-arch1-1")),
+            ),
+            (
+                "nvidia, 545.29.06, 6.6.8-arch1-1, x86_64: installed",
+                Some(("nvidia/545.29.06", "6.6.8-arch1-1")),
+            ),
+            (
+                "v4l2loopback/0.12.7, 6.1.69-1-lts, x86_64: installed (WARNING! Diff between built and installed module!)",
+                Some(("v4l2loopback/0.12.7", "6.1.69-1-lts")),
+            ),
+            // Added but not built for any kernel
+            ("zfs/2.2.2: added", None),
+            ("zfs, 2.2.2: added", None),
+            ("", None),
+        ] {
+            let parsed = parsed.map(|(module, kernel)| (module.to_string(), kernel));
+            assert_eq!(DkmsLeftovers::parse_status(line), parsed, "{line}");
+        }
     }
 }
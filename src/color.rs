@@ -0,0 +1,61 @@
+//! The output is colored with ANSI escape codes, unless disabled with
+//! `--color never` or the `NO_COLOR` environment variable. By default, it's
+//! also disabled when stdout isn't a terminal, so that the escape codes don't
+//! end up in files.
+
+use std::{
+    env,
+    fmt::Display,
+    io::{self, IsTerminal},
+    str::FromStr,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+static ENABLED: AtomicBool = AtomicBool::new(true);
+
+#[derive(Default, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "unknown color choice '{s}', expected `auto`, `always` or `never`"
+            )),
+        }
+    }
+}
+
+/// Enables or disables the colors for the rest of the program.
+pub fn init(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        // Only a non-empty value counts, as specified in https://no-color.org
+        ColorChoice::Auto => {
+            env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+                && io::stdout().is_terminal()
+        }
+    };
+    ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// The text with the given ANSI style, e.g. `36;1` for bold cyan, if the
+/// colors are enabled.
+pub fn paint(style: &str, text: impl Display) -> String {
+    if ENABLED.load(Ordering::Relaxed) {
+        format!("\x1b[{style}m{text}\x1b[0m")
+    } else {
+        text.to_string()
+    }
+}
@@ -0,0 +1,510 @@
+//! Completion scripts for bash, zsh and fish. argh can't generate them by
+//! itself, so the options are taken from its `--help` output, and the command
+//! identifiers and profiles from the registry.
+
+use arch_clean::{
+    config::ConfigFile,
+    registry::{self, Category},
+};
+
+use std::{fmt::Write, str::FromStr};
+
+/// The shells completions can be generated for
+#[derive(Clone, Copy)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    const NAMES: [&'static str; 3] = ["bash", "zsh", "fish"];
+}
+
+impl FromStr for Shell {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bash" => Ok(Shell::Bash),
+            "zsh" => Ok(Shell::Zsh),
+            "fish" => Ok(Shell::Fish),
+            _ => Err(format!(
+                "unknown shell '{s}', expected `bash`, `zsh` or `fish`"
+            )),
+        }
+    }
+}
+
+/// The options that can be given more than once, which argh doesn't show in
+/// its help
+const REPEATABLE: [&str; 2] = ["--verbose", "--project-root"];
+
+/// What an option takes after it
+enum Value {
+    /// Nothing, it's a switch
+    None,
+    /// Something that can't be completed, like a number
+    Any,
+    Words(Vec<String>),
+    /// A comma-separated list of commands
+    Commands,
+    File,
+    Directory,
+}
+
+struct Flag {
+    long: String,
+    short: Option<char>,
+    description: String,
+    value: Value,
+}
+
+impl Flag {
+    /// E.g. `-q|--quiet`, as in the patterns of a `case`
+    fn pattern(&self) -> String {
+        match self.short {
+            Some(short) => format!("-{short}|{}", self.long),
+            None => self.long.clone(),
+        }
+    }
+
+    /// E.g. `-q --quiet`
+    fn names(&self) -> String {
+        self.pattern().replace('|', " ")
+    }
+}
+
+struct Subcommand {
+    name: String,
+    description: String,
+    flags: Vec<Flag>,
+    /// The values of its positional argument, if it has one
+    positional: Vec<String>,
+}
+
+/// Everything that can be completed
+struct Spec {
+    flags: Vec<Flag>,
+    subcommands: Vec<Subcommand>,
+    /// The identifiers of the commands with their description
+    ids: Vec<(String, String)>,
+}
+
+/// What the option takes, given its name, if it's not a switch.
+fn value(long: &str, file: &ConfigFile) -> Value {
+    let words = |words: &[&str]| Value::Words(words.iter().map(|word| word.to_string()).collect());
+    match long {
+        "--only" | "--skip" | "--apply-only" | "--explain" => Value::Commands,
+        "--profile" => Value::Words(registry::profiles(file)),
+        "--category" => Value::Words(Category::ALL.iter().map(ToString::to_string).collect()),
+        "--min-severity" => words(&["info", "warn", "critical"]),
+        "--log-level" => words(&["error", "warn", "info", "debug", "trace"]),
+        "--color" => words(&["auto", "always", "never"]),
+        "--format" => words(&["text", "json"]),
+        "--bytes" => words(&["raw", "iec", "si"]),
+        "--aur-helper" => words(&["yay", "paru", "pikaur", "trizen"]),
+        "--root-command" => words(&["sudo", "doas", "pkexec"]),
+        "--log-file" | "--report" => Value::File,
+        "--project-root" => Value::Directory,
+        _ => Value::Any,
+    }
+}
+
+/// The entries in a section of the help, e.g. `Options:`, as their names and
+/// their description, which may span multiple lines.
+fn section(help: &str, title: &str) -> Vec<(String, String)> {
+    let mut entries: Vec<(String, String)> = Vec::new();
+    let lines = help.lines().skip_while(|line| *line != title).skip(1);
+    for line in lines.take_while(|line| !line.is_empty()) {
+        let Some(entry) = line.strip_prefix("  ") else {
+            break;
+        };
+
+        if entry.starts_with(' ') {
+            if let Some((_, description)) = entries.last_mut() {
+                if !description.is_empty() {
+                    description.push(' ');
+                }
+                description.push_str(entry.trim());
+            }
+        } else {
+            let (names, description) = entry.split_once("  ").unwrap_or((entry, ""));
+            entries.push((names.to_owned(), description.trim().to_owned()));
+        }
+    }
+
+    entries
+}
+
+/// The options in the help, where the ones that take a value are known from
+/// the usage line, e.g. `[--jobs <jobs>]`.
+fn flags(help: &str, file: &ConfigFile) -> Vec<Flag> {
+    let usage = help.lines().next().unwrap_or_default();
+    section(help, "Options:")
+        .into_iter()
+        .map(|(names, description)| {
+            let (short, long) = match names.split_once(", ") {
+                Some((short, long)) => (short.chars().nth(1), long.to_owned()),
+                None => (None, names),
+            };
+            let value = if usage.contains(&format!("[{long} <")) {
+                value(&long, file)
+            } else {
+                Value::None
+            };
+
+            Flag {
+                long,
+                short,
+                description,
+                value,
+            }
+        })
+        .collect()
+}
+
+impl Spec {
+    /// `help` returns the help of the program given its arguments, like
+    /// `["list", "--help"]`.
+    fn new(help: impl Fn(&[&str]) -> String, file: &ConfigFile) -> Spec {
+        let main = help(&["--help"]);
+        let subcommands = section(&main, "Commands:")
+            .into_iter()
+            .map(|(name, description)| {
+                let help = help(&[&name, "--help"]);
+                let positional = if name == "completions" {
+                    Shell::NAMES.iter().map(|name| name.to_string()).collect()
+                } else {
+                    Vec::new()
+                };
+
+                Subcommand {
+                    flags: flags(&help, file),
+                    name,
+                    description,
+                    positional,
+                }
+            })
+            .collect();
+
+        Spec {
+            flags: flags(&main, file),
+            subcommands,
+            ids: registry::ids(file),
+        }
+    }
+
+    /// All the options, including the ones of the subcommands.
+    fn all_flags(&self) -> impl Iterator<Item = &Flag> {
+        let subcommands = self.subcommands.iter().flat_map(|sub| &sub.flags);
+        self.flags.iter().chain(subcommands)
+    }
+}
+
+/// The text quoted for zsh.
+fn zsh_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// The description of an option or value in the brackets of zsh's specs.
+fn zsh_description(text: &str) -> String {
+    format!("[{}]", text.replace('[', "\\[").replace(']', "\\]"))
+}
+
+/// The text quoted for fish.
+fn fish_quote(text: &str) -> String {
+    format!("'{}'", text.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+fn bash(spec: &Spec) -> String {
+    let ids = spec
+        .ids
+        .iter()
+        .map(|(id, _)| id.as_str())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut out = String::new();
+    writeln!(out, "_arch_clean() {{").unwrap();
+    writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"").unwrap();
+    writeln!(out, "    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"").unwrap();
+    writeln!(out, "    local ids=\"{ids}\"").unwrap();
+    writeln!(out).unwrap();
+
+    // The values of the option right before, the same for all subcommands
+    writeln!(out, "    case \"$prev\" in").unwrap();
+    let mut arms: Vec<(String, Vec<String>)> = Vec::new();
+    for flag in spec.all_flags() {
+        if matches!(flag.value, Value::None) {
+            continue;
+        }
+
+        let completion = match &flag.value {
+            Value::None | Value::Any => String::new(),
+            Value::Words(words) => format!(
+                "\n            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))",
+                words.join(" ")
+            ),
+            Value::Commands => "
+            local prefix=\"\"
+            [[ $cur == *,* ]] && prefix=\"${cur%,*},\"
+            compopt -o nospace
+            COMPREPLY=($(compgen -P \"$prefix\" -W \"$ids\" -- \"${cur##*,}\"))"
+                .to_owned(),
+            Value::File => "\n            COMPREPLY=($(compgen -f -- \"$cur\"))".to_owned(),
+            Value::Directory => "\n            COMPREPLY=($(compgen -d -- \"$cur\"))".to_owned(),
+        };
+        // The options with the same completion share their arm
+        let pattern = flag.pattern();
+        match arms.iter_mut().find(|(other, _)| *other == completion) {
+            Some((_, patterns)) if !patterns.contains(&pattern) => patterns.push(pattern),
+            Some(_) => {}
+            None => arms.push((completion, vec![pattern])),
+        }
+    }
+    for (completion, patterns) in arms {
+        writeln!(out, "        {}){completion}", patterns.join("|")).unwrap();
+        writeln!(out, "            return ;;").unwrap();
+    }
+    writeln!(out, "    esac\n").unwrap();
+
+    let names = spec
+        .subcommands
+        .iter()
+        .map(|sub| sub.name.as_str())
+        .collect::<Vec<_>>();
+    writeln!(out, "    local i subcommand=\"\"").unwrap();
+    writeln!(out, "    for ((i = 1; i < COMP_CWORD; i++)); do").unwrap();
+    writeln!(out, "        case \"${{COMP_WORDS[i]}}\" in").unwrap();
+    writeln!(
+        out,
+        "            {}) subcommand=\"${{COMP_WORDS[i]}}\"; break ;;",
+        names.join("|")
+    )
+    .unwrap();
+    writeln!(out, "        esac").unwrap();
+    writeln!(out, "    done\n").unwrap();
+
+    let words = |flags: &[Flag], extra: &[&str]| {
+        let flags = flags.iter().map(Flag::names);
+        let extra = extra.iter().map(|word| word.to_string());
+        flags.chain(extra).collect::<Vec<_>>().join(" ")
+    };
+    writeln!(out, "    local words").unwrap();
+    writeln!(out, "    case \"$subcommand\" in").unwrap();
+    for sub in &spec.subcommands {
+        let positional = sub
+            .positional
+            .iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>();
+        let words = words(&sub.flags, &positional);
+        writeln!(out, "        {}) words=\"{words}\" ;;", sub.name).unwrap();
+    }
+    writeln!(
+        out,
+        "        *) words=\"{}\" ;;",
+        words(&spec.flags, &names) + " $ids"
+    )
+    .unwrap();
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "    COMPREPLY=($(compgen -W \"$words\" -- \"$cur\"))").unwrap();
+    writeln!(out, "}}\n").unwrap();
+    writeln!(out, "complete -F _arch_clean arch-clean").unwrap();
+
+    out
+}
+
+/// The `_arguments` spec of an option, e.g. `'--jobs[...]:jobs: '`.
+fn zsh_flag(flag: &Flag) -> String {
+    let repeat = if REPEATABLE.contains(&flag.long.as_str()) {
+        "*"
+    } else {
+        ""
+    };
+    let name = flag.long.trim_start_matches('-');
+    let value = match &flag.value {
+        Value::None => String::new(),
+        Value::Any => format!(":{name}: "),
+        Value::Words(words) => format!(":{name}:({})", words.join(" ")),
+        Value::Commands => ":command:_arch_clean_ids".to_owned(),
+        Value::File => ":file:_files".to_owned(),
+        Value::Directory => ":directory:_files -/".to_owned(),
+    };
+
+    let spec = format!("{}{value}", zsh_description(&flag.description));
+    // `{-q,--quiet}` is expanded by zsh to both names with the same spec
+    match flag.short {
+        Some(short) if repeat.is_empty() => format!(
+            "'(-{short} {long})'{{-{short},{long}}}{}",
+            zsh_quote(&spec),
+            long = flag.long
+        ),
+        Some(short) => format!("'*'{{-{short},{}}}{}", flag.long, zsh_quote(&spec)),
+        None => zsh_quote(&format!("{repeat}{}{spec}", flag.long)),
+    }
+}
+
+fn zsh(spec: &Spec) -> String {
+    let mut out = String::new();
+    writeln!(out, "#compdef arch-clean\n").unwrap();
+    writeln!(out, "_arch_clean_ids() {{").unwrap();
+    writeln!(out, "    _values -s , command \\").unwrap();
+    let ids = spec
+        .ids
+        .iter()
+        .map(|(id, description)| {
+            format!(
+                "        {}",
+                zsh_quote(&format!("{id}{}", zsh_description(description)))
+            )
+        })
+        .collect::<Vec<_>>();
+    writeln!(out, "{}", ids.join(" \\\n")).unwrap();
+    writeln!(out, "}}\n").unwrap();
+
+    writeln!(out, "_arch_clean() {{").unwrap();
+    writeln!(out, "    local context state state_descr line").unwrap();
+    writeln!(out, "    typeset -A opt_args\n").unwrap();
+    // The options are also completed after the identifier of a command
+    writeln!(out, "    local -a flags=(").unwrap();
+    for flag in &spec.flags {
+        writeln!(out, "        {}", zsh_flag(flag)).unwrap();
+    }
+    writeln!(out, "    )\n").unwrap();
+    writeln!(out, "    _arguments -C $flags \\").unwrap();
+    writeln!(out, "        '1: :->subcommand' \\").unwrap();
+    writeln!(out, "        '*:: :->args'\n").unwrap();
+
+    writeln!(out, "    case $state in").unwrap();
+    writeln!(out, "        subcommand)").unwrap();
+    writeln!(out, "            local -a subcommands=(").unwrap();
+    for sub in &spec.subcommands {
+        let item = format!("{}:{}", sub.name, sub.description);
+        writeln!(out, "                {}", zsh_quote(&item)).unwrap();
+    }
+    writeln!(out, "            )").unwrap();
+    writeln!(out, "            local -a ids=(").unwrap();
+    for (id, description) in &spec.ids {
+        let item = format!("{id}:{description}");
+        writeln!(out, "                {}", zsh_quote(&item)).unwrap();
+    }
+    writeln!(out, "            )").unwrap();
+    writeln!(out, "            _describe subcommand subcommands").unwrap();
+    writeln!(out, "            _describe command ids ;;").unwrap();
+    writeln!(out, "        args)").unwrap();
+    writeln!(out, "            case $words[1] in").unwrap();
+    for sub in &spec.subcommands {
+        writeln!(out, "                {})", sub.name).unwrap();
+        write!(out, "                    _arguments").unwrap();
+        for flag in &sub.flags {
+            write!(out, " \\\n                        {}", zsh_flag(flag)).unwrap();
+        }
+        if !sub.positional.is_empty() {
+            let positional = format!("1:{}:({})", sub.name, sub.positional.join(" "));
+            write!(
+                out,
+                " \\\n                        {}",
+                zsh_quote(&positional)
+            )
+            .unwrap();
+        }
+        writeln!(out, " ;;").unwrap();
+    }
+    writeln!(out, "                *) _arguments $flags ;;").unwrap();
+    writeln!(out, "            esac ;;").unwrap();
+    writeln!(out, "    esac").unwrap();
+    writeln!(out, "}}\n").unwrap();
+    writeln!(out, "_arch_clean \"$@\"").unwrap();
+
+    out
+}
+
+/// The `complete` line of an option, only when the condition holds.
+fn fish_flag(flag: &Flag, condition: &str) -> String {
+    let mut line = format!("complete -c arch-clean -n {}", fish_quote(condition));
+    if let Some(short) = flag.short {
+        write!(line, " -s {short}").unwrap();
+    }
+    write!(line, " -l {}", flag.long.trim_start_matches('-')).unwrap();
+    match &flag.value {
+        Value::None => {}
+        Value::Any => line.push_str(" -x"),
+        Value::Words(words) => write!(line, " -x -a {}", fish_quote(&words.join(" "))).unwrap(),
+        Value::Commands => line.push_str(" -x -a '(__fish_complete_list , __arch_clean_ids)'"),
+        Value::File => line.push_str(" -r -F"),
+        Value::Directory => line.push_str(" -x -a '(__fish_complete_directories)'"),
+    }
+    write!(line, " -d {}", fish_quote(&flag.description)).unwrap();
+
+    line
+}
+
+fn fish(spec: &Spec) -> String {
+    let mut out = String::new();
+    writeln!(out, "function __arch_clean_ids").unwrap();
+    writeln!(out, "    printf '%s\\t%s\\n' \\").unwrap();
+    let ids = spec
+        .ids
+        .iter()
+        .map(|(id, description)| format!("        {} {}", fish_quote(id), fish_quote(description)))
+        .collect::<Vec<_>>();
+    writeln!(out, "{}", ids.join(" \\\n")).unwrap();
+    writeln!(out, "end\n").unwrap();
+
+    let names = spec
+        .subcommands
+        .iter()
+        .map(|sub| sub.name.as_str())
+        .collect::<Vec<_>>();
+    let main = format!("not __fish_seen_subcommand_from {}", names.join(" "));
+    writeln!(out, "complete -c arch-clean -f").unwrap();
+    for flag in &spec.flags {
+        writeln!(out, "{}", fish_flag(flag, &main)).unwrap();
+    }
+    for sub in &spec.subcommands {
+        writeln!(
+            out,
+            "complete -c arch-clean -n {} -a {} -d {}",
+            fish_quote(&main),
+            sub.name,
+            fish_quote(&sub.description)
+        )
+        .unwrap();
+    }
+    writeln!(
+        out,
+        "complete -c arch-clean -n {} -a '(__arch_clean_ids)'",
+        fish_quote(&main)
+    )
+    .unwrap();
+
+    for sub in &spec.subcommands {
+        let condition = format!("__fish_seen_subcommand_from {}", sub.name);
+        for flag in &sub.flags {
+            writeln!(out, "{}", fish_flag(flag, &condition)).unwrap();
+        }
+        if !sub.positional.is_empty() {
+            writeln!(
+                out,
+                "complete -c arch-clean -n {} -a {}",
+                fish_quote(&condition),
+                fish_quote(&sub.positional.join(" "))
+            )
+            .unwrap();
+        }
+    }
+
+    out
+}
+
+/// The completion script for the shell. `help` returns the help of the
+/// program given its arguments, like `["list", "--help"]`.
+pub fn generate(shell: Shell, help: impl Fn(&[&str]) -> String, file: &ConfigFile) -> String {
+    let spec = Spec::new(help, file);
+    match shell {
+        Shell::Bash => bash(&spec),
+        Shell::Zsh => zsh(&spec),
+        Shell::Fish => fish(&spec),
+    }
+}
@@ -0,0 +1,386 @@
+//! The configuration may be set both in a file and with command-line
+//! arguments, the latter taking precedence. The file is located at
+//! `~/.config/arch-clean/config.toml` and may include a section per command
+//! to override the global options, e.g.:
+//!
+//! ```toml
+//! max-age = 60
+//!
+//! [commands.rust-target]
+//! project-roots = ["/home/mario/Programming"]
+//! ```
+
+use crate::{
+    cmd::{ByteFormat, Severity, Size},
+    i18n::Lang,
+    registry::Category,
+};
+
+use std::{collections::HashMap, env, fs, io, path::PathBuf, thread};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+/// How the fix of a command is applied with `apply`. By default, it's
+/// confirmed unless `yes` is set.
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConfirmPolicy {
+    /// Confirmed even with `yes`, for the risky ones
+    AlwaysAsk,
+    /// Applied without confirmation, for the trusted ones
+    Auto,
+    /// Never applied
+    Never,
+}
+
+/// The options that can be set by any of the sources. All of them are
+/// optional so that they can be merged, with the defaults being applied only
+/// at the end.
+#[derive(Deserialize, Default, Clone, Debug)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct Options {
+    pub apply: Option<bool>,
+    pub yes: Option<bool>,
+    pub confirm: Option<ConfirmPolicy>,
+    pub permanent: Option<bool>,
+    pub max_packages: Option<usize>,
+    pub max_disk_usage: Option<usize>,
+    pub max_age: Option<u64>,
+    pub max_log_size: Option<u64>,
+    pub keep: Option<usize>,
+    pub purge_uninstalled: Option<bool>,
+    pub warn_size: Option<u64>,
+    pub critical_size: Option<u64>,
+    pub min_severity: Option<Severity>,
+    pub min_size: Option<Size>,
+    pub fixes_only: Option<bool>,
+    pub hide_empty: Option<bool>,
+    pub aur_helper: Option<String>,
+    pub root_command: Option<String>,
+    pub timeout: Option<u64>,
+    pub cache_ttl: Option<u64>,
+    pub refresh: Option<bool>,
+    pub jobs: Option<usize>,
+    pub project_roots: Option<Vec<PathBuf>>,
+    pub profile: Option<String>,
+    pub category: Option<String>,
+    pub group: Option<bool>,
+    pub all_users: Option<bool>,
+    pub hosts: Option<Vec<String>>,
+    pub lang: Option<Lang>,
+    pub bytes: Option<ByteFormat>,
+    pub pre_fix: Option<String>,
+    pub post_fix: Option<String>,
+    pub pre_run: Option<String>,
+    pub post_run: Option<String>,
+}
+
+impl Options {
+    /// Overrides the options with the ones set in `other`.
+    pub fn merge(self, other: &Options) -> Options {
+        let other = other.clone();
+        Options {
+            apply: other.apply.or(self.apply),
+            yes: other.yes.or(self.yes),
+            confirm: other.confirm.or(self.confirm),
+            permanent: other.permanent.or(self.permanent),
+            max_packages: other.max_packages.or(self.max_packages),
+            max_disk_usage: other.max_disk_usage.or(self.max_disk_usage),
+            max_age: other.max_age.or(self.max_age),
+            max_log_size: other.max_log_size.or(self.max_log_size),
+            keep: other.keep.or(self.keep),
+            purge_uninstalled: other.purge_uninstalled.or(self.purge_uninstalled),
+            warn_size: other.warn_size.or(self.warn_size),
+            critical_size: other.critical_size.or(self.critical_size),
+            min_severity: other.min_severity.or(self.min_severity),
+            min_size: other.min_size.or(self.min_size),
+            fixes_only: other.fixes_only.or(self.fixes_only),
+            hide_empty: other.hide_empty.or(self.hide_empty),
+            aur_helper: other.aur_helper.or(self.aur_helper),
+            root_command: other.root_command.or(self.root_command),
+            timeout: other.timeout.or(self.timeout),
+            cache_ttl: other.cache_ttl.or(self.cache_ttl),
+            refresh: other.refresh.or(self.refresh),
+            jobs: other.jobs.or(self.jobs),
+            project_roots: other.project_roots.or(self.project_roots),
+            profile: other.profile.or(self.profile),
+            category: other.category.or(self.category),
+            group: other.group.or(self.group),
+            all_users: other.all_users.or(self.all_users),
+            hosts: other.hosts.or(self.hosts),
+            lang: other.lang.or(self.lang),
+            bytes: other.bytes.or(self.bytes),
+            pre_fix: other.pre_fix.or(self.pre_fix),
+            post_fix: other.post_fix.or(self.post_fix),
+            pre_run: other.pre_run.or(self.pre_run),
+            post_run: other.post_run.or(self.post_run),
+        }
+    }
+}
+
+/// The contents of the configuration file.
+#[derive(Deserialize, Default, Debug)]
+#[serde(default, rename_all = "kebab-case")]
+pub struct ConfigFile {
+    #[serde(flatten)]
+    pub options: Options,
+    /// Options for specific commands, by their identifier
+    pub commands: HashMap<String, Options>,
+    pub custom: Vec<CustomCheck>,
+    /// Sets of commands to run, by their name. They may replace the built-in
+    /// ones.
+    pub profiles: HashMap<String, Vec<String>>,
+}
+
+/// A command defined by the user, e.g.:
+///
+/// ```toml
+/// [[custom]]
+/// id = "docker-images"
+/// title = "Dangling Docker images"
+/// check = "docker images --quiet --filter dangling=true"
+/// fix = "docker image prune --force"
+/// ```
+#[derive(Deserialize, Clone, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub struct CustomCheck {
+    pub id: String,
+    pub title: Option<String>,
+    /// Shell command whose output is shown
+    pub check: String,
+    /// Regex that the output has to match for the fix to be available
+    pub pattern: Option<String>,
+    /// Value that the first number in the output has to exceed for the fix to
+    /// be available
+    pub threshold: Option<f64>,
+    /// Shell command that applies the fix
+    pub fix: Option<String>,
+    /// Custom commands aren't in any category by default
+    pub category: Option<Category>,
+}
+
+impl CustomCheck {
+    pub fn description(&self) -> String {
+        self.title.clone().unwrap_or_else(|| self.id.clone())
+    }
+}
+
+impl ConfigFile {
+    pub fn path() -> PathBuf {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap()).join(".config"));
+        config_dir.join("arch-clean/config.toml")
+    }
+
+    /// Reads the configuration file, which is optional.
+    pub fn load() -> Result<ConfigFile> {
+        let path = Self::path();
+        let content = match fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ConfigFile::default()),
+            Err(e) => return Err(e).with_context(|| format!("couldn't read {}", path.display())),
+        };
+
+        toml::from_str(&content).with_context(|| format!("invalid config at {}", path.display()))
+    }
+
+    /// The configuration for a command, or the global one if `None`. The
+    /// command-line options take precedence over the section of the command,
+    /// which in turn takes precedence over the global options.
+    pub fn resolve(&self, cmd: Option<&str>, cli: &Options) -> Config {
+        let mut options = self.options.clone();
+        if let Some(cmd_options) = cmd.and_then(|cmd| self.commands.get(cmd)) {
+            options = options.merge(cmd_options);
+        }
+
+        options.merge(cli).into()
+    }
+}
+
+/// The final configuration passed to the commands.
+#[derive(Clone, Debug)]
+pub struct Config {
+    pub apply: bool,
+    /// Apply the fixes without asking for confirmation
+    pub yes: bool,
+    pub confirm: Option<ConfirmPolicy>,
+    /// Remove the files for good instead of moving them to the trash
+    pub permanent: bool,
+    pub max_packages: usize,
+    pub max_disk_usage: usize,
+    pub max_age: u64,
+    pub max_log_size: u64,
+    /// Versions of each package kept in the pacman cache
+    pub keep: usize,
+    /// Remove all the versions of the uninstalled packages from the pacman
+    /// cache too
+    pub purge_uninstalled: bool,
+    /// Size in MiB that the fix has to free for the result to be a warning
+    pub warn_size: u64,
+    /// Size in MiB that the fix has to free for the result to be critical
+    pub critical_size: u64,
+    /// Results less severe than this aren't shown
+    pub min_severity: Severity,
+    /// Bytes that a fix has to free to be flagged, below which the commands
+    /// that list sizes don't report the entries either
+    pub min_size: u64,
+    /// Only show the results with a fix available
+    pub fixes_only: bool,
+    /// Don't show the results with nothing to report
+    pub hide_empty: bool,
+    /// The first one installed is used if not set
+    pub aur_helper: Option<String>,
+    /// Program that runs the fixes that need root, detected if not set
+    pub root_command: Option<String>,
+    /// Seconds after which the check is cancelled, without limit if not set
+    pub timeout: Option<u64>,
+    /// Hours during which the results of the slow checks are reused, see
+    /// [`crate::cache`]. They aren't cached with 0.
+    pub cache_ttl: u64,
+    /// Run the check even if its result is cached
+    pub refresh: bool,
+    /// Maximum of checks running at the same time, only used globally
+    pub jobs: usize,
+    pub project_roots: Vec<PathBuf>,
+    /// The set of commands to run, only used globally
+    pub profile: Option<String>,
+    /// Comma-separated categories of the commands to run, only used globally
+    pub category: Option<String>,
+    /// Whether the results are shown by category, only used globally
+    pub group: bool,
+    /// Whether the per-user commands are run for every user, only used
+    /// globally
+    pub all_users: bool,
+    /// The home directory of the user being checked, if it's not the invoking
+    /// one. It can't be configured, it's set for each user with `all_users`.
+    pub home: Option<PathBuf>,
+    /// The machines to run the checks on over SSH instead of this one, only
+    /// used globally
+    pub hosts: Vec<String>,
+    /// The language of the messages, detected if not set. Only used globally.
+    pub lang: Option<Lang>,
+    /// How the sizes are shown, in bytes with the JSON output and with
+    /// binary prefixes otherwise if not set. Only used globally.
+    pub bytes: Option<ByteFormat>,
+    /// Shell commands run before and after the fix, see [`crate::hooks`]
+    pub pre_fix: Option<String>,
+    pub post_fix: Option<String>,
+    /// Shell commands run before the first fix and after the last one, only
+    /// used globally
+    pub pre_run: Option<String>,
+    pub post_run: Option<String>,
+}
+
+impl From<Options> for Config {
+    fn from(options: Options) -> Self {
+        Config {
+            apply: options.apply.unwrap_or(false),
+            yes: options.yes.unwrap_or(false),
+            confirm: options.confirm,
+            permanent: options.permanent.unwrap_or(false),
+            max_packages: options.max_packages.unwrap_or(10),
+            max_disk_usage: options.max_disk_usage.unwrap_or(10),
+            max_age: options.max_age.unwrap_or(30),
+            max_log_size: options.max_log_size.unwrap_or(100),
+            keep: options.keep.unwrap_or(3),
+            purge_uninstalled: options.purge_uninstalled.unwrap_or(false),
+            warn_size: options.warn_size.unwrap_or(1024),
+            critical_size: options.critical_size.unwrap_or(10 * 1024),
+            min_severity: options.min_severity.unwrap_or_default(),
+            min_size: options.min_size.map_or(0, |size| size.0),
+            fixes_only: options.fixes_only.unwrap_or(false),
+            hide_empty: options.hide_empty.unwrap_or(false),
+            aur_helper: options.aur_helper,
+            root_command: options.root_command,
+            timeout: options.timeout,
+            cache_ttl: options.cache_ttl.unwrap_or(6),
+            refresh: options.refresh.unwrap_or(false),
+            jobs: options
+                .jobs
+                .unwrap_or_else(|| thread::available_parallelism().map_or(4, |jobs| jobs.get())),
+            project_roots: options.project_roots.unwrap_or_default(),
+            profile: options.profile,
+            category: options.category,
+            group: options.group.unwrap_or(false),
+            all_users: options.all_users.unwrap_or(false),
+            home: None,
+            hosts: options.hosts.unwrap_or_default(),
+            lang: options.lang,
+            bytes: options.bytes,
+            pre_fix: options.pre_fix,
+            post_fix: options.post_fix,
+            pre_run: options.pre_run,
+            post_run: options.post_run,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+max-age = 60
+keep = 5
+
+[commands.rust-target]
+max-age = 90
+project-roots = ["/home/mario/Programming"]
+"#;
+
+    #[test]
+    fn merge_options() {
+        let file = Options {
+            max_age: Some(60),
+            keep: Some(5),
+            ..Options::default()
+        };
+        let cli = Options {
+            max_age: Some(120),
+            ..Options::default()
+        };
+        let merged = file.merge(&cli);
+        assert_eq!(merged.max_age, Some(120));
+        assert_eq!(merged.keep, Some(5));
+        assert_eq!(merged.max_packages, None);
+    }
+
+    #[test]
+    fn resolve_precedence() {
+        let file = toml::from_str::<ConfigFile>(CONFIG).unwrap();
+        let cli = Options::default();
+
+        // The global options apply to the commands without a section
+        let config = file.resolve(None, &cli);
+        assert_eq!((config.max_age, config.keep), (60, 5));
+        assert!(config.project_roots.is_empty());
+        let config = file.resolve(Some("orphans"), &cli);
+        assert_eq!((config.max_age, config.keep), (60, 5));
+
+        // The section of the command overrides them, but only where it's set
+        let config = file.resolve(Some("rust-target"), &cli);
+        assert_eq!((config.max_age, config.keep), (90, 5));
+        assert_eq!(
+            config.project_roots,
+            [PathBuf::from("/home/mario/Programming")]
+        );
+
+        // The command line overrides both
+        let cli = Options {
+            max_age: Some(120),
+            ..Options::default()
+        };
+        assert_eq!(file.resolve(None, &cli).max_age, 120);
+        assert_eq!(file.resolve(Some("rust-target"), &cli).max_age, 120);
+        assert_eq!(file.resolve(Some("rust-target"), &cli).keep, 5);
+    }
+
+    #[test]
+    fn resolve_defaults() {
+        let config = ConfigFile::default().resolve(Some("rust-target"), &Options::default());
+        assert_eq!((config.max_age, config.keep), (30, 3));
+        assert!(!config.apply);
+    }
+}
@@ -0,0 +1,199 @@
+//! The checks can also be run on other machines over SSH with `hosts`, e.g.,
+//! `user@machine`. Each of them runs `arch-clean` with the JSON output once,
+//! and its results are shown as if they were local ones, labeled with the
+//! host. Their fixes have to be applied on the host itself.
+
+use crate::{
+    cmd::{self, CleanupCommand, FixResult, Output},
+    config::Config,
+    process::TracedCommand,
+    registry::{self, Check, MissingDependency},
+    t,
+};
+
+use std::{fs, io::Write, path::Path, sync::Arc};
+
+use anyhow::{anyhow, bail, Context, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::{process::Command, sync::OnceCell};
+
+/// The program run on the hosts, which has to be installed there
+const REMOTE_PROGRAM: &str = "arch-clean";
+
+/// A result in the JSON output of the host
+#[derive(Deserialize)]
+struct RemoteResult {
+    id: String,
+    user: Option<String>,
+    title: Option<String>,
+    #[serde(default)]
+    content: Vec<String>,
+    #[serde(default)]
+    fix_available: bool,
+    reclaimable_bytes: Option<u64>,
+    error: Option<String>,
+    missing_dependency: Option<MissingDependency>,
+}
+
+/// A machine whose results are fetched once for all of its checks.
+struct Host {
+    name: String,
+    /// The commands to run on it
+    ids: Vec<String>,
+    refresh: bool,
+    /// The error can't be cloned, so it's kept as a message for every check
+    results: OnceCell<Result<Vec<RemoteResult>, String>>,
+}
+
+impl Host {
+    async fn results(&self) -> Result<&[RemoteResult]> {
+        let results = self
+            .results
+            .get_or_init(|| async { self.fetch().await.map_err(|e| format!("{e:#}")) })
+            .await;
+        results.as_deref().map_err(|e| anyhow!("{e}"))
+    }
+
+    async fn fetch(&self) -> Result<Vec<RemoteResult>> {
+        let mut cmd = Command::new("ssh");
+        // It can't ask for a password, since several run at the same time
+        cmd.args(["-o", "BatchMode=yes", "--", &self.name, REMOTE_PROGRAM])
+            .args(["--format", "json", "--only", &self.ids.join(",")])
+            // The sizes in the content are shown as they would be here
+            .args(["--bytes", &cmd::byte_format().to_string()]);
+        if self.refresh {
+            cmd.arg("--refresh");
+        }
+        let output = cmd.traced_output().await.context("couldn't run ssh")?;
+        // Some of the checks failing there is reported in their results
+        if !output.status.success() && output.status.code() != Some(2) {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.trim().lines().last().unwrap_or_default();
+            bail!("{} exited with {}: {reason}", self.name, output.status);
+        }
+
+        serde_json::from_slice(&output.stdout)
+            .with_context(|| format!("invalid output from {}", self.name))
+    }
+}
+
+/// A command run on a host, see [`Host`].
+struct Remote {
+    host: Arc<Host>,
+    id: String,
+    description: String,
+}
+
+#[async_trait]
+impl CleanupCommand for Remote {
+    async fn check(&mut self, _config: &Config) -> Result<Output> {
+        let host = &self.host.name;
+        // The ones that aren't shown there aren't reported at all
+        let Some(result) = self
+            .host
+            .results()
+            .await?
+            .iter()
+            .find(|result| result.id == self.id && result.user.is_none())
+        else {
+            return Ok(Output {
+                title: format!("[{host}] {}", self.description),
+                content: t!("(none)"),
+                ..Output::default()
+            });
+        };
+
+        if let Some(missing) = &result.missing_dependency {
+            return Err(missing.clone().into());
+        }
+        if let Some(error) = &result.error {
+            bail!("{error}");
+        }
+        Ok(Output {
+            title: format!("[{host}] {}", result.title.as_deref().unwrap_or_default()),
+            content: result.content.join("\n"),
+            fix_available: result.fix_available,
+            reclaimable_bytes: result.reclaimable_bytes,
+        })
+    }
+
+    fn show_fix(&self, _config: &Config, out: &mut dyn Write) -> std::io::Result<()> {
+        let command = format!(
+            "ssh {} {REMOTE_PROGRAM} {} --apply",
+            self.host.name, self.id
+        );
+        writeln!(
+            out,
+            "{}",
+            t!(
+                "This fix has to be applied on the host with '{command}'",
+                command = command
+            )
+        )
+    }
+
+    async fn apply_fix(&self, _config: &Config) -> Result<FixResult> {
+        bail!("the fix can only be applied on {}", self.host.name);
+    }
+}
+
+/// The hosts in a file, one per line. Empty lines and comments starting with
+/// `#` are ignored.
+pub fn read_hosts(path: &Path) -> Result<Vec<String>> {
+    let content =
+        fs::read_to_string(path).with_context(|| format!("couldn't read {}", path.display()))?;
+    Ok(content
+        .lines()
+        .map(|line| line.split('#').next().unwrap_or_default().trim())
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+/// The built-in checks repeated for each of the hosts, in the same order, to
+/// run them there instead. The custom ones are left out, since they're only
+/// defined here.
+pub fn for_hosts(checks: Vec<Check>, hosts: &[String], refresh: bool) -> Vec<Check> {
+    let checks = checks
+        .into_iter()
+        .filter(|check| registry::COMMANDS.iter().any(|entry| entry.id == check.id))
+        .collect::<Vec<_>>();
+    let ids = checks
+        .iter()
+        .map(|check| check.id.clone())
+        .collect::<Vec<_>>();
+    let hosts = hosts
+        .iter()
+        .map(|name| {
+            Arc::new(Host {
+                name: name.clone(),
+                ids: ids.clone(),
+                refresh,
+                results: OnceCell::new(),
+            })
+        })
+        .collect::<Vec<_>>();
+
+    checks
+        .into_iter()
+        .flat_map(|check| {
+            hosts
+                .iter()
+                .map(|host| Check {
+                    id: check.id.clone(),
+                    description: check.description.clone(),
+                    category: check.category,
+                    dependencies: &["ssh"],
+                    cmd: Box::new(Remote {
+                        host: Arc::clone(host),
+                        id: check.id.clone(),
+                        description: check.description.clone(),
+                    }),
+                    user: None,
+                    host: Some(host.name.clone()),
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
@@ -0,0 +1,253 @@
+//! The results of each run are saved in `~/.local/state/arch-clean/runs`, so
+//! that they can be compared with the previous one. Only what's needed for
+//! that is saved: the estimated size and the lines of the output.
+
+use crate::{
+    cmd::{self, human_size},
+    runner::CheckResult,
+    t,
+};
+
+use std::{
+    collections::HashSet,
+    env, fs, io,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Older runs are removed, since only the last one is actually used
+const MAX_RUNS: usize = 100;
+/// Changes in size smaller than this aren't worth reporting
+const MIN_SIZE_CHANGE: u64 = 1024 * 1024;
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct Run {
+    /// Seconds since the Unix epoch
+    pub time: u64,
+    pub results: Vec<RunResult>,
+}
+
+/// A check that ran successfully.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RunResult {
+    pub id: String,
+    pub reclaimable_bytes: Option<u64>,
+    pub findings: Vec<String>,
+}
+
+impl RunResult {
+    /// `None` if the check failed, since there's nothing to compare.
+    pub fn new(result: &CheckResult) -> Option<RunResult> {
+        let out = result.output.as_ref().ok()?;
+        Some(RunResult {
+            id: result.key(),
+            reclaimable_bytes: out.reclaimable_bytes,
+            findings: out
+                .content
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !cmd::is_none(line))
+                .map(ToString::to_string)
+                .collect(),
+        })
+    }
+
+    /// What changed since a previous result of the same check, in a few words.
+    /// The lines of the output usually include the size too, so they're only
+    /// compared when it didn't change.
+    pub fn diff(&self, prev: &RunResult) -> Vec<String> {
+        let mut changes = Vec::new();
+        if let (Some(now), Some(before)) = (self.reclaimable_bytes, prev.reclaimable_bytes) {
+            if now >= before + MIN_SIZE_CHANGE {
+                changes.push(format!("grew by {}", human_size(now - before)));
+            } else if before >= now + MIN_SIZE_CHANGE {
+                changes.push(format!("shrank by {}", human_size(before - now)));
+            }
+        }
+
+        if !changes.is_empty() {
+            return changes;
+        }
+
+        let before = prev.findings.iter().collect::<HashSet<_>>();
+        let now = self.findings.iter().collect::<HashSet<_>>();
+        let new = now.difference(&before).count();
+        let gone = before.difference(&now).count();
+        if new > 0 {
+            changes.push(format!("{new} new"));
+        }
+        if gone > 0 {
+            changes.push(format!("{gone} gone"));
+        }
+
+        changes
+    }
+}
+
+impl Run {
+    pub fn new(results: Vec<RunResult>) -> Run {
+        Run {
+            time: now(),
+            results,
+        }
+    }
+
+    /// The changes in the checks that ran both times, e.g. `trash: grew by
+    /// 2.3 GiB`.
+    pub fn diff(&self, prev: &Run) -> Vec<String> {
+        self.results
+            .iter()
+            .filter_map(|result| {
+                let prev = prev.results.iter().find(|prev| prev.id == result.id)?;
+                let changes = result.diff(prev);
+                (!changes.is_empty()).then(|| format!("{}: {}", result.id, changes.join(", ")))
+            })
+            .collect()
+    }
+
+    /// How long ago the run was, for humans.
+    pub fn age(&self) -> String {
+        ago(self.time)
+    }
+}
+
+/// Seconds since the Unix epoch.
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// How long ago something happened in a few words, e.g. `7 days ago`, given
+/// the seconds since the Unix epoch.
+pub(crate) fn ago(time: u64) -> String {
+    let hours = now().saturating_sub(time) / 3600;
+    match hours {
+        0 => t!("less than an hour ago"),
+        1..=47 => t!("{hours} hours ago", hours = hours),
+        _ => t!("{days} days ago", days = hours / 24),
+    }
+}
+
+fn dir() -> PathBuf {
+    let state_dir = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap()).join(".local/state"));
+    state_dir.join("arch-clean/runs")
+}
+
+/// The saved runs, from oldest to newest. They're named after their time, so
+/// that sorting them by name is enough.
+fn paths() -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e).context("couldn't read the run history"),
+    };
+    let mut paths = entries
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect::<Vec<_>>();
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// The last saved run, if any.
+pub fn last() -> Result<Option<Run>> {
+    let path = match paths()?.pop() {
+        Some(path) => path,
+        None => return Ok(None),
+    };
+    let content = fs::read_to_string(&path)?;
+    let run = serde_json::from_str(&content)
+        .with_context(|| format!("invalid run history at {}", path.display()))?;
+
+    Ok(Some(run))
+}
+
+/// Saves the run, removing the oldest ones if there are too many.
+pub fn save(run: &Run) -> Result<()> {
+    let dir = dir();
+    fs::create_dir_all(&dir).with_context(|| format!("couldn't create {}", dir.display()))?;
+    // Zero-padded so that they're sorted by name
+    let path = dir.join(format!("{:020}.json", run.time));
+    fs::write(&path, serde_json::to_string(run)?)
+        .with_context(|| format!("couldn't write {}", path.display()))?;
+
+    let paths = paths()?;
+    for old in paths.iter().take(paths.len().saturating_sub(MAX_RUNS)) {
+        fs::remove_file(old)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MIB: u64 = 1024 * 1024;
+
+    fn result(id: &str, reclaimable_bytes: Option<u64>, findings: &[&str]) -> RunResult {
+        RunResult {
+            id: id.to_string(),
+            reclaimable_bytes,
+            findings: findings.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    #[test]
+    fn diff_runs() {
+        let prev = Run::new(vec![
+            result("trash", Some(MIB), &[]),
+            result("paccache", Some(500 * MIB), &[]),
+            result("orphans", None, &["foo 1.0-1", "bar 2.0-1"]),
+            result("nvim-swap", None, &["~/.local/state/nvim/swap/a.swp"]),
+            result("journal", Some(100 * MIB), &[]),
+        ]);
+        let now = Run::new(vec![
+            result("trash", Some(3 * MIB + MIB / 2), &[]),
+            result("paccache", Some(200 * MIB), &[]),
+            result("orphans", None, &["foo 1.0-1", "baz 0.1-1", "qux 0.2-1"]),
+            result("nvim-swap", None, &["~/.local/state/nvim/swap/a.swp"]),
+            // Below the minimum change
+            result("journal", Some(100 * MIB + 1024), &[]),
+            // Only checked in this run
+            result("disk-usage", Some(MIB), &["/home 10G"]),
+        ]);
+
+        assert_eq!(
+            now.diff(&prev),
+            [
+                "trash: grew by 2.5 MiB",
+                "paccache: shrank by 300.0 MiB",
+                "orphans: 2 new, 1 gone",
+            ]
+        );
+    }
+
+    #[test]
+    fn diff_gone_checks() {
+        // The checks that didn't run this time aren't compared
+        let prev = Run::new(vec![
+            result("trash", Some(10 * MIB), &[]),
+            result("orphans", None, &["foo 1.0-1"]),
+        ]);
+        let now = Run::new(vec![result("trash", Some(10 * MIB), &[])]);
+        assert!(now.diff(&prev).is_empty());
+        assert!(prev.diff(&now).is_empty());
+    }
+
+    #[test]
+    fn diff_size_over_findings() {
+        // The findings usually include the size, so they aren't compared when
+        // it changed
+        let prev = result("rust-target", Some(10 * MIB), &["~/a/target (10.0 MiB)"]);
+        let now = result("rust-target", Some(20 * MIB), &["~/a/target (20.0 MiB)"]);
+        assert_eq!(now.diff(&prev), ["grew by 10.0 MiB"]);
+    }
+}
@@ -0,0 +1,32 @@
+//! Shell commands configured to run around the fixes, e.g.:
+//!
+//! ```toml
+//! # Once, before the first fix and after the last one
+//! post-run = "updatedb"
+//!
+//! # Around the fix of a specific command
+//! [commands.orphans]
+//! pre-fix = "snapper create --description arch-clean"
+//! ```
+//!
+//! They receive what they're about in `ARCH_CLEAN_*` environment variables.
+
+use crate::process::TracedCommand;
+
+use anyhow::{bail, Result};
+use tokio::process::Command;
+
+/// Runs the hook with `sh`, failing if it doesn't succeed.
+pub(crate) async fn run(hook: &str, env: &[(&str, String)]) -> Result<()> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(hook)
+        .envs(env.iter().map(|(key, value)| (key, value)))
+        .traced_status()
+        .await?;
+    if !status.success() {
+        bail!("hook '{hook}' exited with {status}");
+    }
+
+    Ok(())
+}
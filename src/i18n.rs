@@ -0,0 +1,123 @@
+//! The messages shown to the user are written in English, which is also what
+//! they're looked up by in the catalog of the language, as with gettext. The
+//! catalogs are in `locales`, e.g., `locales/es.toml`:
+//!
+//! ```toml
+//! "Orphan packages" = "Paquetes huérfanos"
+//! "{checks} checks run" = "{checks} comprobaciones ejecutadas"
+//! ```
+//!
+//! The language is the one given with `--lang`, or the one in the environment
+//! as usual, i.e., `LC_ALL`, `LC_MESSAGES` or `LANG`. The messages that
+//! haven't been translated are shown in English.
+
+use std::{collections::HashMap, env, fmt, str::FromStr, sync::OnceLock};
+
+use serde::Deserialize;
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+static CATALOG: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    En,
+    Es,
+}
+
+impl Lang {
+    /// The language of the environment, English if it's not supported.
+    pub fn detect() -> Lang {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| env::var(var).ok())
+            .find(|locale| !locale.is_empty())
+            .and_then(|locale| locale.parse().ok())
+            .unwrap_or(Lang::En)
+    }
+
+    fn catalog(self) -> &'static str {
+        match self {
+            Lang::En => "",
+            Lang::Es => include_str!("../locales/es.toml"),
+        }
+    }
+}
+
+impl FromStr for Lang {
+    type Err = String;
+
+    /// Also accepts locales, e.g. `es_ES.UTF-8`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lang = s.split(['_', '.', '@']).next().unwrap_or_default();
+        match lang {
+            "en" | "C" | "POSIX" => Ok(Lang::En),
+            "es" => Ok(Lang::Es),
+            _ => Err(format!("unsupported language '{s}', expected `en` or `es`")),
+        }
+    }
+}
+
+/// Sets the language of the messages, which is detected if not given. It can
+/// only be set once, before any message is shown.
+pub fn init(lang: Option<Lang>) {
+    let _ = LANG.set(lang.unwrap_or_else(Lang::detect));
+}
+
+/// The message in the current language, with each `{name}` in it replaced by
+/// its argument. See [`crate::t`].
+pub fn translate(msg: &str, args: &[(&str, &dyn fmt::Display)]) -> String {
+    let catalog = CATALOG.get_or_init(|| {
+        let lang = *LANG.get_or_init(Lang::detect);
+        // The catalogs are embedded, so they're known to be fine
+        toml::from_str(lang.catalog()).expect("invalid message catalog")
+    });
+
+    let mut translated = catalog.get(msg).map_or(msg, String::as_str).to_string();
+    for (name, value) in args {
+        translated = translated.replace(&format!("{{{name}}}"), &value.to_string());
+    }
+    translated
+}
+
+/// Translates a message with [`i18n::translate`](crate::i18n::translate),
+/// with the arguments given like in [`format!`], but always by name:
+///
+/// ```
+/// # use arch_clean::t;
+/// # let n = 3;
+/// let title = t!("Last {count} explicitly installed packages", count = n);
+/// ```
+#[macro_export]
+macro_rules! t {
+    ($msg:literal $(, $name:ident = $value:expr)* $(,)?) => {
+        $crate::i18n::translate(
+            $msg,
+            &[$((stringify!($name), &$value as &dyn ::std::fmt::Display)),*],
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_locales() {
+        assert_eq!("es".parse(), Ok(Lang::Es));
+        assert_eq!("es_ES.UTF-8".parse(), Ok(Lang::Es));
+        assert_eq!("es_MX@euro".parse(), Ok(Lang::Es));
+        assert_eq!("en_US.UTF-8".parse(), Ok(Lang::En));
+        assert_eq!("C".parse(), Ok(Lang::En));
+        assert_eq!("C.UTF-8".parse(), Ok(Lang::En));
+        assert_eq!("POSIX".parse(), Ok(Lang::En));
+        assert!("fr_FR.UTF-8".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn catalogs_are_valid() {
+        for lang in [Lang::En, Lang::Es] {
+            toml::from_str::<HashMap<String, String>>(lang.catalog()).unwrap();
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::render::{Event, Renderer};
+
+use arch_clean::{
+    registry::{Category, MissingDependency},
+    Output, Severity,
+};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// A result in the JSON output
+#[derive(Serialize)]
+struct JsonResult<'a> {
+    id: &'a str,
+    category: Option<Category>,
+    /// The user whose home directory was checked, if it's not the invoking one
+    user: Option<&'a str>,
+    /// The machine it was run on, if it's not this one
+    host: Option<&'a str>,
+    #[serde(flatten)]
+    output: Option<&'a Output>,
+    severity: Option<Severity>,
+    /// With all of its causes, also for the skipped ones
+    error: Option<String>,
+    /// Whether the error is that a dependency is missing
+    skipped: bool,
+    missing_dependency: Option<&'a MissingDependency>,
+}
+
+/// The results printed at the end as a JSON array, in the same order as the
+/// commands.
+#[derive(Default)]
+pub struct Json {
+    /// The results shown, by the position of their check
+    results: Vec<(usize, serde_json::Value)>,
+}
+
+impl Renderer for Json {
+    fn render(&mut self, event: Event<'_>) -> Result<()> {
+        match event {
+            Event::CheckFinished(result) if result.is_shown() => {
+                let json = JsonResult {
+                    id: &result.id,
+                    category: result.category,
+                    user: result.user.as_deref(),
+                    host: result.host.as_deref(),
+                    output: result.output.as_ref().ok(),
+                    severity: result.severity(),
+                    error: result.output.as_ref().err().map(|e| format!("{e:#}")),
+                    skipped: result.skipped().is_some(),
+                    missing_dependency: result.skipped(),
+                };
+                self.results
+                    .push((result.index, serde_json::to_value(json)?));
+            }
+            Event::Finished(_) => {
+                self.results.sort_by_key(|(index, _)| *index);
+                let results = self
+                    .results
+                    .iter()
+                    .map(|(_, result)| result)
+                    .collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&results)?);
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,40 @@
+//! The cleanup commands can also be embedded in other programs. [`Runner`]
+//! takes care of running them concurrently with their configuration:
+//!
+//! ```no_run
+//! # async fn example() -> anyhow::Result<()> {
+//! use arch_clean::Runner;
+//!
+//! let runner = Runner::load()?;
+//! let checks = runner.select(Some("orphans,trash"), None)?;
+//! for result in runner.spawn(checks).collect().await? {
+//!     if let Ok(out) = result.output {
+//!         println!("{out}");
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod cache;
+pub mod cmd;
+pub mod color;
+pub mod config;
+pub mod fleet;
+pub mod history;
+pub mod hooks;
+pub mod i18n;
+pub mod registry;
+pub mod runner;
+pub mod timer;
+pub mod undo;
+pub mod users;
+
+mod aur;
+mod pacman;
+mod pkg;
+mod privilege;
+mod process;
+
+pub use cmd::{CleanupCommand, FixResult, Output, Severity, Size};
+pub use runner::Runner;
@@ -1,113 +1,674 @@
-mod cmd;
+mod completions;
+mod json;
+mod progress;
+mod render;
+mod report;
+mod text;
+mod tui;
+mod watch;
 
-use cmd::CleanupCommand;
+use completions::Shell;
+use json::Json;
+use render::{Renderer, Summary};
+use report::Report;
+use text::Text;
+use tui::Tui;
+use watch::Watch;
+
+use arch_clean::{
+    cmd::{self, ByteFormat},
+    color::{self, ColorChoice},
+    config::{ConfigFile, Options},
+    fleet,
+    history::{self, Run, RunResult},
+    i18n::{self, Lang},
+    registry::{self, Check},
+    t, timer,
+    undo::{self, Change},
+    Runner, Severity, Size,
+};
 
 use std::{
-    io::{self, Write},
-    sync::Arc,
+    env,
+    fs::File,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::Mutex,
+    time::Duration,
 };
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use argh::FromArgs;
-use tokio::{sync::mpsc, task};
+use tokio::time;
+use tracing_subscriber::filter::LevelFilter;
 
 #[derive(FromArgs)]
 /// Clean up your Arch installation, real fast.
-pub struct Config {
+struct Args {
     /// apply the suggested fix
     #[argh(switch)]
     apply: bool,
 
-    /// maximum of explicitly installed packages to be shown
-    #[argh(option, default = "10")]
-    max_packages: usize,
+    /// comma-separated list of the only commands whose fix is applied, while
+    /// all of them are still checked
+    #[argh(option)]
+    apply_only: Option<String>,
+
+    /// print what a command inspects, requires and risks, and what its fix
+    /// would run right now, without applying it
+    #[argh(option)]
+    explain: Option<String>,
+
+    /// don't ask for confirmation when applying fixes, e.g., to run from a
+    /// timer, leaving alone the items that the fixes confirm one by one
+    #[argh(switch)]
+    yes: bool,
+
+    /// remove the files for good instead of moving them to the trash
+    #[argh(switch)]
+    permanent: bool,
+
+    /// maximum of explicitly installed packages to be shown (default: 10)
+    #[argh(option)]
+    max_packages: Option<usize>,
+
+    /// maximum of disk usage entries to be shown (default: 10)
+    #[argh(option)]
+    max_disk_usage: Option<usize>,
+
+    /// number of days after which unused files are considered stale
+    /// (default: 30)
+    #[argh(option)]
+    max_age: Option<u64>,
+
+    /// size in MiB after which log files are considered oversized
+    /// (default: 100)
+    #[argh(option)]
+    max_log_size: Option<u64>,
+
+    /// versions of each package kept in the pacman cache by `paccache`
+    /// (default: 3)
+    #[argh(option)]
+    keep: Option<usize>,
+
+    /// remove all the cached versions of the uninstalled packages with
+    /// `paccache` too
+    #[argh(switch)]
+    purge_uninstalled: bool,
+
+    /// size in MiB that a fix has to free for its result to be a warning
+    /// (default: 1024)
+    #[argh(option)]
+    warn_size: Option<u64>,
+
+    /// size in MiB that a fix has to free for its result to be critical
+    /// (default: 10240)
+    #[argh(option)]
+    critical_size: Option<u64>,
+
+    /// only show the results at least this severe, either `info`, `warn` or
+    /// `critical` (default: info)
+    #[argh(option)]
+    min_severity: Option<Severity>,
+
+    /// only flag the fixes and the entries that list sizes above this, e.g.
+    /// `100M` (default: 0)
+    #[argh(option)]
+    min_size: Option<Size>,
+
+    /// only show the results with a fix available
+    #[argh(switch)]
+    fixes_only: bool,
+
+    /// don't show the results with nothing to report, e.g. `(none)`
+    #[argh(switch)]
+    hide_empty: bool,
+
+    /// AUR helper to use, like `paru` (default: the first of yay, paru,
+    /// pikaur or trizen that is installed)
+    #[argh(option)]
+    aur_helper: Option<String>,
+
+    /// program to run the fixes that need root, like `doas` (default: the
+    /// first of sudo, doas or pkexec that is installed)
+    #[argh(option)]
+    root_command: Option<String>,
+
+    /// seconds after which a check is cancelled (default: no limit)
+    #[argh(option)]
+    timeout: Option<u64>,
+
+    /// run the slow checks again even if their results are cached, e.g.
+    /// `disk-usage`
+    #[argh(switch)]
+    refresh: bool,
+
+    /// maximum of checks to run at the same time (default: the number of
+    /// CPUs)
+    #[argh(option)]
+    jobs: Option<usize>,
+
+    /// directory to look for projects in, can be repeated (defaults to the
+    /// home directory)
+    #[argh(option)]
+    project_root: Vec<PathBuf>,
+
+    /// comma-separated list of the only commands to run
+    #[argh(option)]
+    only: Option<String>,
+
+    /// comma-separated list of commands not to run
+    #[argh(option)]
+    skip: Option<String>,
+
+    /// comma-separated list of the categories of the commands to run, among
+    /// `packages`, `caches`, `disk`, `development` and `system`
+    #[argh(option)]
+    category: Option<String>,
+
+    /// show the results grouped by category, under a header for each
+    #[argh(switch)]
+    group: bool,
+
+    /// also run the checks of the home directory for every user in `/home`,
+    /// which needs root
+    #[argh(switch)]
+    all_users: bool,
+
+    /// machine to run the checks on over SSH instead of this one, e.g.
+    /// `user@machine`, can be repeated
+    #[argh(option)]
+    host: Vec<String>,
+
+    /// file with the machines to run the checks on, one per line
+    #[argh(option)]
+    hosts_file: Option<PathBuf>,
+
+    /// the set of commands to run, either `desktop`, `server`, `developer` or
+    /// one in the configuration
+    #[argh(option)]
+    profile: Option<String>,
+
+    /// show the results in an interactive interface, where the fixes to
+    /// apply can be selected
+    #[argh(switch)]
+    tui: bool,
+
+    /// only show the results with findings and a one-line summary
+    #[argh(switch, short = 'q')]
+    quiet: bool,
+
+    /// also show the external commands that are run and how long they take,
+    /// and with `-vv` more details
+    #[argh(switch, short = 'v')]
+    verbose: u8,
+
+    /// log verbosity, from `error` to `trace` (default: off, or info with
+    /// --log-file)
+    #[argh(option)]
+    log_level: Option<LevelFilter>,
+
+    /// file to append the logs to instead of stderr
+    #[argh(option)]
+    log_file: Option<PathBuf>,
+
+    /// show what changed since the previous run
+    #[argh(switch)]
+    diff: bool,
+
+    /// run the checks again every given seconds, highlighting what changed
+    #[argh(option)]
+    watch: Option<u64>,
+
+    /// also send a desktop notification with the summary, e.g., when run
+    /// from a timer
+    #[argh(switch)]
+    notify: bool,
+
+    /// also write the results to a report, in Markdown or HTML according to
+    /// its extension, e.g. `report.html`
+    #[argh(option)]
+    report: Option<PathBuf>,
+
+    /// when to color the output, either `auto`, `always` or `never`
+    /// (default: auto)
+    #[argh(option, default = "ColorChoice::Auto")]
+    color: ColorChoice,
+
+    /// language of the messages, either `en` or `es` (default: the one in
+    /// `LANG`)
+    #[argh(option)]
+    lang: Option<Lang>,
+
+    /// how the sizes are shown, either `raw` in bytes, `iec` with binary
+    /// prefixes or `si` with decimal ones (default: raw with the JSON output,
+    /// iec otherwise)
+    #[argh(option)]
+    bytes: Option<ByteFormat>,
+
+    /// output format, either `text` or `json` (default: text)
+    #[argh(option, default = "Format::Text")]
+    format: Format,
+
+    /// identifier of the only command to run, e.g. `orphans`
+    #[argh(positional)]
+    id: Option<String>,
+
+    #[argh(subcommand)]
+    subcommand: Option<Subcommand>,
+}
+
+#[derive(FromArgs)]
+#[argh(subcommand)]
+enum Subcommand {
+    List(List),
+    Undo(Undo),
+    InstallTimer(InstallTimer),
+    Completions(Completions),
+}
+
+#[derive(FromArgs)]
+/// List the available commands and their dependencies.
+#[argh(subcommand, name = "list")]
+struct List {}
+
+#[derive(FromArgs)]
+/// Revert the last applied fix, where possible.
+#[argh(subcommand, name = "undo")]
+struct Undo {}
+
+#[derive(FromArgs)]
+/// Run the checks periodically with a user-level systemd timer, without
+/// applying any fix.
+#[argh(subcommand, name = "install-timer")]
+struct InstallTimer {
+    /// when to run, in the format of systemd's `OnCalendar` (default: weekly)
+    #[argh(option, default = "String::from(\"weekly\")")]
+    on_calendar: String,
+
+    /// send a desktop notification with the summary of each run
+    #[argh(switch)]
+    notify: bool,
+}
+
+#[derive(FromArgs)]
+/// Print the completion script for a shell, e.g. to save it in
+/// `/usr/share/bash-completion/completions/arch-clean`.
+#[argh(subcommand, name = "completions")]
+struct Completions {
+    /// either `bash`, `zsh` or `fish`
+    #[argh(positional)]
+    shell: Shell,
+}
+
+/// How the results are printed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Text,
+    Json,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            _ => Err(format!("unknown format '{s}', expected `text` or `json`")),
+        }
+    }
+}
+
+impl Args {
+    /// The checks to run, where a single command can be given by its
+    /// identifier instead of with the selection options.
+    fn select(&self, runner: &Runner) -> Result<Vec<Check>> {
+        let Some(id) = &self.id else {
+            return runner.select(self.only.as_deref(), self.skip.as_deref());
+        };
+        if self.only.is_some()
+            || self.skip.is_some()
+            || self.profile.is_some()
+            || self.category.is_some()
+        {
+            bail!(
+                "a single command can't be combined with --only, --skip, --profile or --category"
+            );
+        }
+        runner.select_one(id)
+    }
+
+    /// The options that were actually specified, so that they only override
+    /// the ones in the configuration file in that case.
+    fn options(&self) -> Result<Options> {
+        let mut hosts = self.host.clone();
+        if let Some(path) = &self.hosts_file {
+            hosts.extend(fleet::read_hosts(path)?);
+        }
+
+        Ok(Options {
+            apply: (self.apply || self.apply_only.is_some()).then_some(true),
+            yes: self.yes.then_some(true),
+            permanent: self.permanent.then_some(true),
+            max_packages: self.max_packages,
+            max_disk_usage: self.max_disk_usage,
+            max_age: self.max_age,
+            max_log_size: self.max_log_size,
+            keep: self.keep,
+            purge_uninstalled: self.purge_uninstalled.then_some(true),
+            warn_size: self.warn_size,
+            critical_size: self.critical_size,
+            min_severity: self.min_severity,
+            min_size: self.min_size,
+            // Being quiet is about showing only what can be done
+            fixes_only: (self.fixes_only || self.quiet).then_some(true),
+            hide_empty: self.hide_empty.then_some(true),
+            aur_helper: self.aur_helper.clone(),
+            root_command: self.root_command.clone(),
+            timeout: self.timeout,
+            refresh: self.refresh.then_some(true),
+            jobs: self.jobs,
+            project_roots: (!self.project_root.is_empty()).then(|| self.project_root.clone()),
+            profile: self.profile.clone(),
+            category: self.category.clone(),
+            group: self.group.then_some(true),
+            all_users: self.all_users.then_some(true),
+            hosts: (!hosts.is_empty()).then_some(hosts),
+            lang: self.lang,
+            bytes: self.bytes,
+            // The hooks can only be set in the configuration file
+            ..Options::default()
+        })
+    }
+}
+
+/// Same as [`argh::from_env`], but also accepting combined short switches,
+/// e.g. `-vv` as `-v -v`.
+fn parse_args() -> Args {
+    let mut strings = env::args();
+    let cmd = strings.next().unwrap_or_default();
+    let strings = strings
+        .flat_map(|arg| {
+            let combined = arg.len() > 2
+                && arg.starts_with('-')
+                && !arg.starts_with("--")
+                && arg[1..].chars().all(|c| c == 'v' || c == 'q');
+            if combined {
+                arg[1..].chars().map(|c| format!("-{c}")).collect()
+            } else {
+                vec![arg]
+            }
+        })
+        .collect::<Vec<_>>();
+    let strs = strings.iter().map(String::as_str).collect::<Vec<_>>();
 
-    /// maximum of disk usage entries to be shown
-    #[argh(option, default = "10")]
-    max_disk_usage: usize,
+    let name = Path::new(&cmd)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&cmd);
+    Args::from_args(&[name], &strs).unwrap_or_else(|early_exit| {
+        match early_exit.status {
+            Ok(()) => println!("{}", early_exit.output),
+            Err(()) => eprintln!("{}", early_exit.output),
+        }
+        process::exit(i32::from(early_exit.status.is_err()))
+    })
 }
 
-impl std::fmt::Debug for Box<dyn CleanupCommand> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str("cleanup command")
+/// Logs to stderr, or to a file if given, including the external commands
+/// that are run. It's mostly useful to find out what a fix did.
+fn init_logging(level: Option<LevelFilter>, file: Option<&Path>) -> Result<()> {
+    let logs = tracing_subscriber::fmt().with_target(false);
+    match file {
+        Some(path) => {
+            let file = File::options()
+                .create(true)
+                .append(true)
+                .open(path)
+                .with_context(|| format!("couldn't open {}", path.display()))?;
+            logs.with_max_level(level.unwrap_or(LevelFilter::INFO))
+                .with_ansi(false)
+                .with_writer(Mutex::new(file))
+                .init();
+        }
+        None => logs
+            .with_max_level(level.unwrap_or(LevelFilter::OFF))
+            .with_writer(io::stderr)
+            .init(),
     }
+
+    Ok(())
 }
 
-/// The fix is a two-step process, first we make sure that the user wants to
-/// continue. This is a blocking operation.
-fn prompt_user(conf: &Config, cmd: &dyn CleanupCommand) -> Result<bool> {
-    cmd.show_fix(conf);
-    print!("\x1b[33mConfirm? [y/N]:\x1b[0m ");
-    let mut confirm = String::new();
-    io::stdout().flush()?;
-    io::stdin().read_line(&mut confirm)?;
+/// See [`registry::explain`], followed by what the fix would do right now, for
+/// which the check is run.
+async fn explain(runner: &Runner, id: &str) -> Result<()> {
+    if !registry::explain(id, runner.config_file())? {
+        return Ok(());
+    }
+
+    println!();
+    for result in runner.spawn(runner.select_one(id)?).collect().await? {
+        if let Some(missing) = result.skipped() {
+            eprintln!(
+                "{}",
+                t!(
+                    "Skipped {id}: {reason}",
+                    id = result.key(),
+                    reason = missing
+                )
+            );
+            continue;
+        }
+        match &result.output {
+            Err(e) => eprintln!("{}: {e}", t!("Failed to run command")),
+            Ok(out) if out.fix_available => result.show_fix()?,
+            Ok(_) => println!("{}", t!("There's nothing to fix right now")),
+        }
+    }
 
-    Ok(confirm.trim() == "y")
+    Ok(())
+}
+
+/// Shows what the last fix did and reverts it after confirmation.
+async fn undo_last(runner: &Runner) -> Result<()> {
+    let record = match undo::last().await? {
+        Some(record) => record,
+        None => {
+            println!("{}", t!("There's nothing to undo"));
+            return Ok(());
+        }
+    };
+
+    println!(
+        "{}",
+        t!("The last fix applied was {id}, which:", id = record.id)
+    );
+    for change in &record.changes {
+        match change {
+            Change::Trashed { path } => {
+                println!("  {}", t!("Trashed {path}", path = path.display()))
+            }
+            Change::Moved { from, .. } => {
+                println!("  {}", t!("Removed {path}", path = from.display()))
+            }
+            Change::RemovedPackages { pkgs } => {
+                println!("  {}", t!("Uninstalled {pkgs}", pkgs = pkgs.join(" ")))
+            }
+        }
+    }
+
+    let config = runner.config(Some(&record.id));
+    if config.yes || cmd::confirm(&t!("Undo?"))? {
+        undo::undo(&record, &config).await?;
+    }
+
+    Ok(())
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // The commands are accompanied by their titles and a suggested fix between
-    // parenthesis.
-    let cmds: [Box<dyn CleanupCommand>; 8] = [
-        Box::new(cmd::LastInstalled::default()),
-        Box::new(cmd::OrphanPackages::default()),
-        Box::new(cmd::Paccache::default()),
-        Box::new(cmd::TrashSize::default()),
-        Box::new(cmd::DiskUsage::default()),
-        Box::new(cmd::DevUpdates::default()),
-        Box::new(cmd::NeovimSwapFiles::default()),
-        Box::new(cmd::RustTarget::default()),
-    ];
-
-    // Quick config with argh
-    let conf = Arc::new(argh::from_env());
-
-    // The check commands are each run in a separate task
-    let (wr, mut rd) = mpsc::unbounded_channel();
-    let mut handles = Vec::with_capacity(cmds.len());
-    for mut cmd in cmds {
-        let wr = wr.clone();
-        let conf = Arc::clone(&conf);
-        handles.push(task::spawn(async move {
-            let output = cmd.check(&conf).await;
-            wr.send((cmd, output)).unwrap();
-        }));
-    }
-    drop(wr); // The channel will be closed automatically
-
-    // Synchonizing the results from the tasks
-    while let Some((cmd, out)) = rd.recv().await {
-        match out {
-            Err(e) => eprintln!("Failed to run command: {e}"),
-            Ok(out) => {
-                println!("{out}");
-
-                // The fixes are applied sequentially so that the user sees the
-                // results of the command. They will only be applied when
-                // configured and if the command actually has a fix available
-                if !conf.apply || !out.fix_available {
-                    continue;
-                }
-
-                if !prompt_user(&conf, &*cmd)? {
-                    println!("\x1b[31mSkipped\x1b[0m\n");
-                    continue;
-                }
-
-                cmd.apply_fix(&conf).await.unwrap_or_else(|e| {
-                    eprintln!("Failed to apply fix: {e}");
-                });
-                println!("\x1b[32mDone\x1b[0m\n");
-            }
+    // Quick config with argh, on top of the configuration file
+    let args = parse_args();
+    color::init(args.color);
+    let verbosity = match args.verbose {
+        0 => None,
+        1 => Some(LevelFilter::INFO),
+        _ => Some(LevelFilter::DEBUG),
+    };
+    let log_level = args.log_level.or(verbosity);
+    init_logging(log_level, args.log_file.as_deref())?;
+    let runner = Runner::new(ConfigFile::load()?, args.options()?);
+    i18n::init(runner.config(None).lang);
+    // Scripts reading the JSON output want the exact sizes
+    let bytes = match args.format {
+        Format::Json => ByteFormat::Raw,
+        Format::Text => ByteFormat::Iec,
+    };
+    cmd::init_byte_format(runner.config(None).bytes.unwrap_or(bytes));
+    match args.subcommand {
+        Some(Subcommand::List(_)) => {
+            registry::list(runner.config_file());
+            return Ok(());
+        }
+        Some(Subcommand::Undo(_)) => return undo_last(&runner).await,
+        Some(Subcommand::InstallTimer(InstallTimer {
+            on_calendar,
+            notify,
+        })) => {
+            let dir = timer::install(&on_calendar, notify).await?;
+            let dir = dir.display();
+            println!("{}", t!("Installed the timer in {dir}", dir = dir));
+            return Ok(());
+        }
+        Some(Subcommand::Completions(Completions { shell })) => {
+            let help = |args: &[&str]| {
+                Args::from_args(&["arch-clean"], args)
+                    .err()
+                    .map(|early_exit| early_exit.output)
+                    .unwrap_or_default()
+            };
+            print!(
+                "{}",
+                completions::generate(shell, help, runner.config_file())
+            );
+            return Ok(());
         }
+        None => {}
+    }
+    if let Some(id) = &args.explain {
+        return explain(&runner, id).await;
     }
 
-    // Wait for any work left in the tasks, which should be none at this point
-    // anyway.
-    for handle in handles {
-        handle.await?;
+    let checks = args.select(&runner)?;
+    if let Some(ids) = &args.apply_only {
+        registry::parse_ids(ids, &runner.config_file().custom)?;
+    }
+    let apply = args.apply || args.apply_only.is_some();
+    if args.format == Format::Json && apply {
+        bail!("fixes can't be applied with the JSON output");
+    }
+    if !runner.config(None).hosts.is_empty() && (apply || args.tui) {
+        bail!("the fixes can only be applied on the hosts themselves, without --apply or --tui");
     }
+    if args.format == Format::Json && args.diff {
+        bail!("the differences can't be shown with the JSON output");
+    }
+    if let Some(interval) = args.watch {
+        if apply || args.tui || args.format == Format::Json {
+            bail!("watch mode can't be used with --apply, --tui or the JSON output");
+        }
+        return watch(&runner, &args, interval).await;
+    }
+    if args.tui && args.report.is_some() {
+        bail!("the report can't be written with the TUI");
+    }
+    if args.tui {
+        let (_, summary) = render::run(&runner, checks, &mut Tui::new(), None).await?;
+        exit_on_failure(&summary);
+        return Ok(());
+    }
+    let mut report = args.report.as_deref().map(Report::new).transpose()?;
 
+    let mut frontend: Box<dyn Renderer> = match args.format {
+        Format::Json => Box::<Json>::default(),
+        Format::Text => {
+            // The logs would be mixed up with the progress
+            let logs_to_stderr =
+                args.log_file.is_none() && log_level.is_some_and(|l| l != LevelFilter::OFF);
+            Box::new(Text::new(
+                args.quiet,
+                args.apply_only.as_deref(),
+                runner.config(None).group,
+                !logs_to_stderr,
+                // Both the results and the progress have to be in the terminal
+                !apply && io::stdout().is_terminal(),
+            ))
+        }
+    };
+    let (results, summary) =
+        render::run(&runner, checks, frontend.as_mut(), report.as_mut()).await?;
+    if let Some(report) = &report {
+        report.save()?;
+    }
+    if args.notify {
+        summary.notify().await?;
+    }
+
+    let run = Run::new(results.iter().filter_map(RunResult::new).collect());
+    if args.diff {
+        print_diff(&run)?;
+    }
+    history::save(&run)?;
+    exit_on_failure(&summary);
+    Ok(())
+}
+
+/// Exits with 2 if any check or fix failed once the run is done, to tell it
+/// apart from arch-clean itself failing, which exits with 1.
+fn exit_on_failure(summary: &Summary) {
+    if summary.failed() {
+        process::exit(2);
+    }
+}
+
+/// Runs the checks over and over, replacing the previous results on screen once
+/// the new ones are ready. The history isn't saved in this mode, since it would
+/// be filled with almost identical runs.
+async fn watch(runner: &Runner, args: &Args, interval: u64) -> Result<()> {
+    let mut frontend = Watch::new(interval, args.quiet, runner.config(None).group);
+    loop {
+        render::run(runner, args.select(runner)?, &mut frontend, None).await?;
+        time::sleep(Duration::from_secs(interval)).await;
+    }
+}
+
+/// Compares the run with the previous one.
+fn print_diff(run: &Run) -> Result<()> {
+    let prev = match history::last()? {
+        Some(prev) => prev,
+        None => {
+            println!("{}", t!("No previous run to compare with"));
+            return Ok(());
+        }
+    };
+
+    println!(
+        "{}",
+        color::paint(
+            "36;1",
+            t!("Changes since the last run, {age}:", age = prev.age())
+        )
+    );
+    let changes = run.diff(&prev);
+    if changes.is_empty() {
+        println!("{}", t!("(none)"));
+    }
+    for change in changes {
+        println!("{change}");
+    }
     Ok(())
 }
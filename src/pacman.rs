@@ -0,0 +1,146 @@
+//! Queries to the local pacman database. By default they run `pacman` and
+//! parse its output. With the `alpm` feature the database is read directly
+//! with libalpm instead, which is faster and doesn't depend on the format of
+//! the output. The commands use them through [`crate::pkg::Pacman`].
+
+#[cfg(not(feature = "alpm"))]
+use crate::process::TracedCommand;
+
+#[cfg(not(feature = "alpm"))]
+use std::process::Stdio;
+use std::{collections::HashSet, path::Path};
+
+use anyhow::Result;
+#[cfg(not(feature = "alpm"))]
+use tokio::process::Command;
+#[cfg(feature = "alpm")]
+use tokio::task;
+
+#[cfg(feature = "alpm")]
+const ROOT: &str = "/";
+#[cfg(feature = "alpm")]
+const DB_PATH: &str = "/var/lib/pacman";
+
+/// Runs pacman with the given arguments and returns its output by lines.
+#[cfg(not(feature = "alpm"))]
+async fn query(args: &[&str]) -> Result<Vec<String>> {
+    let cmd = Command::new("pacman").args(args).traced_output().await?;
+    let stdout = String::from_utf8(cmd.stdout)?;
+
+    Ok(stdout.lines().map(ToString::to_string).collect())
+}
+
+/// Runs a query on the local database. libalpm is blocking, and its handle
+/// can't be shared between threads, so a new one is opened in a blocking
+/// task.
+#[cfg(feature = "alpm")]
+async fn query<T, F>(f: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&alpm::Db) -> T + Send + 'static,
+{
+    task::spawn_blocking(move || {
+        let handle = alpm::Alpm::new(ROOT, DB_PATH)?;
+        Ok(f(handle.localdb()))
+    })
+    .await?
+}
+
+/// Names of all the installed packages.
+#[cfg(not(feature = "alpm"))]
+pub async fn installed() -> Result<Vec<String>> {
+    query(&["-Qq"]).await
+}
+
+#[cfg(feature = "alpm")]
+pub async fn installed() -> Result<Vec<String>> {
+    query(|db| db.pkgs().iter().map(|pkg| pkg.name().to_string()).collect()).await
+}
+
+/// Names of the explicitly installed packages.
+#[cfg(not(feature = "alpm"))]
+pub async fn explicit() -> Result<HashSet<String>> {
+    Ok(query(&["-Qqe"]).await?.into_iter().collect())
+}
+
+#[cfg(feature = "alpm")]
+pub async fn explicit() -> Result<HashSet<String>> {
+    query(|db| {
+        db.pkgs()
+            .iter()
+            .filter(|pkg| pkg.reason() == alpm::PackageReason::Explicit)
+            .map(|pkg| pkg.name().to_string())
+            .collect()
+    })
+    .await
+}
+
+/// Names of the packages installed as dependencies that aren't required nor
+/// optionally required by any other, same as `pacman -Qtd`.
+#[cfg(not(feature = "alpm"))]
+pub async fn orphans() -> Result<Vec<String>> {
+    query(&["-Qqtd"]).await
+}
+
+#[cfg(feature = "alpm")]
+pub async fn orphans() -> Result<Vec<String>> {
+    query(|db| {
+        db.pkgs()
+            .iter()
+            .filter(|pkg| pkg.reason() == alpm::PackageReason::Depend)
+            .filter(|pkg| pkg.required_by().is_empty() && pkg.optional_for().is_empty())
+            .map(|pkg| pkg.name().to_string())
+            .collect()
+    })
+    .await
+}
+
+/// Absolute paths of the files installed by all the packages.
+#[cfg(not(feature = "alpm"))]
+pub async fn files() -> Result<Vec<String>> {
+    query(&["-Qlq"]).await
+}
+
+#[cfg(feature = "alpm")]
+pub async fn files() -> Result<Vec<String>> {
+    // The paths in the database are relative to the root
+    query(|db| {
+        db.pkgs()
+            .iter()
+            .flat_map(|pkg| {
+                pkg.files()
+                    .files()
+                    .iter()
+                    .map(|file| format!("{ROOT}{}", String::from_utf8_lossy(file.name())))
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    })
+    .await
+}
+
+/// Whether any of the installed packages owns the file.
+#[cfg(not(feature = "alpm"))]
+pub async fn is_owned(path: &Path) -> Result<bool> {
+    let status = Command::new("pacman")
+        .arg("-Qqo")
+        .arg(path)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .traced_status()
+        .await?;
+
+    Ok(status.success())
+}
+
+#[cfg(feature = "alpm")]
+pub async fn is_owned(path: &Path) -> Result<bool> {
+    let path = path.to_string_lossy().into_owned();
+    let path = path.trim_start_matches('/').to_string();
+    query(move |db| {
+        db.pkgs()
+            .iter()
+            .any(|pkg| pkg.files().contains(path.as_str()).is_some())
+    })
+    .await
+}
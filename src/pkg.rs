@@ -0,0 +1,127 @@
+//! The package-related commands go through a [`PkgBackend`] instead of running
+//! pacman themselves, so that other package managers, e.g. the ones of the Arch
+//! derivatives, can be supported by implementing it. For now there's only
+//! [`Pacman`].
+
+use crate::{aur::AurHelper, config::Config, pacman, privilege, process::TracedCommand};
+
+use std::{collections::HashSet, path::Path};
+
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use tokio::process::Command;
+
+#[async_trait]
+pub trait PkgBackend: Sync + Send {
+    /// Names of all the installed packages.
+    async fn installed(&self) -> Result<Vec<String>>;
+
+    /// Names of the explicitly installed packages.
+    async fn explicit(&self) -> Result<HashSet<String>>;
+
+    /// Names of the packages installed as dependencies that no other one
+    /// needs.
+    async fn orphans(&self) -> Result<Vec<String>>;
+
+    /// Absolute paths of the files installed by all the packages.
+    async fn files(&self) -> Result<Vec<String>>;
+
+    /// Whether any of the installed packages owns the file.
+    async fn is_owned(&self, path: &Path) -> Result<bool>;
+
+    /// What [`PkgBackend::remove`] runs, to show it to the user.
+    fn remove_command(&self, config: &Config, pkgs: &[String]) -> String;
+
+    /// Uninstalls the packages along with the dependencies that aren't needed
+    /// anymore, without confirmation.
+    async fn remove(&self, config: &Config, pkgs: &[String]) -> Result<()>;
+
+    /// Installs the packages as dependencies, skipping the ones that already
+    /// are, e.g., to undo [`PkgBackend::remove`].
+    async fn install_as_deps(&self, config: &Config, pkgs: &[String]) -> Result<()>;
+}
+
+/// The backend for the system, with the configured AUR helper if any.
+pub fn backend(config: &Config) -> Box<dyn PkgBackend> {
+    Box::new(Pacman {
+        helper: AurHelper::detect(config),
+    })
+}
+
+/// The database is queried as described in [`crate::pacman`], and the packages
+/// are installed and removed with the AUR helper if there's one, since they
+/// may be from the AUR.
+pub struct Pacman {
+    helper: Option<AurHelper>,
+}
+
+impl Pacman {
+    /// The AUR helper takes care of asking for privileges, and otherwise
+    /// pacman is run as root.
+    async fn command(&self, config: &Config) -> Result<Command> {
+        match &self.helper {
+            Some(helper) => Ok(helper.command()),
+            None => privilege::command(config, "pacman").await,
+        }
+    }
+}
+
+#[async_trait]
+impl PkgBackend for Pacman {
+    async fn installed(&self) -> Result<Vec<String>> {
+        pacman::installed().await
+    }
+
+    async fn explicit(&self) -> Result<HashSet<String>> {
+        pacman::explicit().await
+    }
+
+    async fn orphans(&self) -> Result<Vec<String>> {
+        pacman::orphans().await
+    }
+
+    async fn files(&self) -> Result<Vec<String>> {
+        pacman::files().await
+    }
+
+    async fn is_owned(&self, path: &Path) -> Result<bool> {
+        pacman::is_owned(path).await
+    }
+
+    fn remove_command(&self, config: &Config, pkgs: &[String]) -> String {
+        let program = match &self.helper {
+            Some(helper) => helper.program().to_string(),
+            None => format!("{}pacman", privilege::prefix(config)),
+        };
+        format!("{program} -Rns --noconfirm {}", pkgs.join(" "))
+    }
+
+    async fn remove(&self, config: &Config, pkgs: &[String]) -> Result<()> {
+        self.command(config)
+            .await?
+            .arg("-Rns")
+            .arg("--noconfirm")
+            .args(pkgs)
+            .traced_output()
+            .await?;
+
+        Ok(())
+    }
+
+    async fn install_as_deps(&self, config: &Config, pkgs: &[String]) -> Result<()> {
+        let status = self
+            .command(config)
+            .await?
+            .arg("-S")
+            .arg("--asdeps")
+            .arg("--needed")
+            .args(pkgs)
+            .traced_status()
+            .await?;
+        if !status.success() {
+            bail!("couldn't reinstall {}", pkgs.join(" "));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,71 @@
+//! Some of the fixes need root. Unless we already are, their commands are run
+//! with sudo, doas or pkexec, whichever is configured or installed. The
+//! password is asked for once, before the first of them.
+
+use crate::{cmd::command_exists, config::Config, process::TracedCommand};
+
+use std::os::unix::fs::MetadataExt;
+
+use anyhow::{bail, Result};
+use tokio::{process::Command, sync::OnceCell};
+
+/// The programs that are detected, in order of preference
+const KNOWN: [&str; 3] = ["sudo", "doas", "pkexec"];
+
+/// All the programs separated by `|`, as a dependency in the registry
+pub const DEPENDENCY: &str = "sudo|doas|pkexec";
+
+static AUTHENTICATED: OnceCell<()> = OnceCell::const_new();
+
+/// The effective user of the process owns its directory in `/proc`.
+pub fn is_root() -> bool {
+    std::fs::metadata("/proc/self").is_ok_and(|meta| meta.uid() == 0)
+}
+
+/// The program that runs commands as root, or `None` if we already are.
+fn program(config: &Config) -> Option<&str> {
+    if is_root() {
+        return None;
+    }
+
+    config
+        .root_command
+        .as_deref()
+        .or_else(|| KNOWN.into_iter().find(|program| command_exists(program)))
+        .or(Some(KNOWN[0]))
+}
+
+/// How the commands that need root are prefixed, e.g. `sudo `, to show them
+/// to the user.
+pub fn prefix(config: &Config) -> String {
+    program(config).map_or_else(String::new, |program| format!("{program} "))
+}
+
+/// Caches the credentials so that they aren't asked for by each command.
+/// pkexec can't do that, so it's left to ask every time.
+async fn authenticate(program: &str) -> Result<()> {
+    let status = match program {
+        "pkexec" => return Ok(()),
+        "sudo" => Command::new("sudo").arg("-v").traced_status().await,
+        _ => Command::new(program).arg("true").traced_status().await,
+    };
+    match status {
+        Ok(status) if status.success() => Ok(()),
+        Ok(_) => bail!("couldn't get root privileges with {program}"),
+        Err(e) => bail!("couldn't run {program}, it can be set with `root-command`: {e}"),
+    }
+}
+
+/// A command that runs `program` as root. Fails if the privileges couldn't be
+/// obtained, instead of leaving it to the command.
+pub async fn command(config: &Config, program: &str) -> Result<Command> {
+    let root = match self::program(config) {
+        Some(root) => root,
+        None => return Ok(Command::new(program)),
+    };
+
+    AUTHENTICATED.get_or_try_init(|| authenticate(root)).await?;
+    let mut cmd = Command::new(root);
+    cmd.arg(program);
+    Ok(cmd)
+}
@@ -0,0 +1,51 @@
+//! External commands are run through [`TracedCommand`], which logs them and
+//! how they exit. That's usually what's needed to find out why a fix didn't
+//! do anything. They're killed if they're dropped before exiting, e.g., when
+//! a fix is interrupted with Ctrl-C.
+
+use std::{
+    io,
+    process::{ExitStatus, Output},
+    time::Instant,
+};
+
+use async_trait::async_trait;
+use tokio::process::Command;
+use tracing::info;
+
+#[async_trait]
+pub trait TracedCommand {
+    /// Same as [`Command::output`].
+    async fn traced_output(&mut self) -> io::Result<Output>;
+
+    /// Same as [`Command::status`], with the standard streams inherited.
+    async fn traced_status(&mut self) -> io::Result<ExitStatus>;
+}
+
+/// Logs the result of the command and how long it took, which may not have
+/// been possible to run at all.
+fn log_exit(cmd: &Command, start: Instant, status: Result<ExitStatus, &io::Error>) {
+    let cmd = format!("{:?}", cmd.as_std());
+    let elapsed = start.elapsed();
+    match status {
+        Ok(status) => info!(%cmd, %status, ?elapsed, "command exited"),
+        Err(e) => info!(%cmd, error = %e, "command couldn't run"),
+    }
+}
+
+#[async_trait]
+impl TracedCommand for Command {
+    async fn traced_output(&mut self) -> io::Result<Output> {
+        let start = Instant::now();
+        let output = self.kill_on_drop(true).output().await;
+        log_exit(self, start, output.as_ref().map(|output| output.status));
+        output
+    }
+
+    async fn traced_status(&mut self) -> io::Result<ExitStatus> {
+        let start = Instant::now();
+        let status = self.kill_on_drop(true).status().await;
+        log_exit(self, start, status.as_ref().copied());
+        status
+    }
+}
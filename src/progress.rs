@@ -0,0 +1,215 @@
+use arch_clean::{color, registry::Check};
+
+use ratatui::crossterm::terminal;
+
+use std::{
+    collections::VecDeque,
+    io::{self, IsTerminal, Write},
+    time::Instant,
+};
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+/// More lines than this would scroll the terminal, which breaks updating
+/// them in place.
+const MAX_LINES: usize = 5;
+
+/// A check as shown on screen
+struct Entry {
+    description: String,
+    done: bool,
+    /// What it shows in place of its placeholder once done, if anything
+    preview: Option<String>,
+}
+
+/// The checks that are still running, shown below the results as they are
+/// printed. It's written to stderr and only when it's a terminal, so that
+/// the output isn't polluted when redirected.
+///
+/// With placeholders, all the checks are listed from the start instead, and
+/// each is filled in as it finishes. The results are then printed in the
+/// same order as the checks, once the ones above them are done too.
+pub struct Progress {
+    /// By the position of the checks
+    checks: Vec<Entry>,
+    /// The checks whose result can be printed, see [`Progress::next`]
+    ready: VecDeque<usize>,
+    /// The first check that isn't ready, with placeholders
+    first: usize,
+    start: Instant,
+    tick: usize,
+    /// Number of rows currently on screen
+    drawn: usize,
+    enabled: bool,
+    placeholders: bool,
+}
+
+impl Progress {
+    /// It can be disabled, e.g., if something else is written to stderr
+    /// meanwhile. The placeholders are only used when it's enabled.
+    pub fn new(checks: &[Check], enabled: bool, placeholders: bool) -> Self {
+        let enabled = enabled && io::stderr().is_terminal();
+        Progress {
+            checks: checks
+                .iter()
+                .map(|check| Entry {
+                    description: check.description.clone(),
+                    done: false,
+                    preview: None,
+                })
+                .collect(),
+            ready: VecDeque::new(),
+            first: 0,
+            start: Instant::now(),
+            tick: 0,
+            drawn: 0,
+            enabled,
+            placeholders: enabled && placeholders,
+        }
+    }
+
+    /// Marks a check as finished, with what it shows once it's its turn.
+    pub fn done(&mut self, index: usize, preview: Option<String>) {
+        let entry = &mut self.checks[index];
+        entry.done = true;
+        if !self.placeholders {
+            self.ready.push_back(index);
+            return;
+        }
+
+        entry.preview = preview;
+        while self.checks.get(self.first).is_some_and(|entry| entry.done) {
+            self.ready.push_back(self.first);
+            self.first += 1;
+        }
+    }
+
+    /// The next check whose result can be printed, in the order they finish,
+    /// or in the order of the checks with placeholders.
+    pub fn next(&mut self) -> Option<usize> {
+        self.ready.pop_front()
+    }
+
+    /// Redraws the pending checks in place.
+    pub fn draw(&mut self) -> io::Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        self.clear()?;
+        let spinner = SPINNER[self.tick % SPINNER.len()];
+        let elapsed = self.start.elapsed().as_secs();
+        self.tick += 1;
+
+        // Some terminals don't report their size
+        let (cols, rows) = terminal::size()
+            .ok()
+            .filter(|(cols, rows)| *cols > 0 && *rows > 0)
+            .map_or((80, 24), |(cols, rows)| {
+                (usize::from(cols), usize::from(rows))
+            });
+        let lines = if self.placeholders {
+            self.placeholder_lines(spinner, elapsed, cols, rows)
+        } else {
+            self.pending_lines(spinner, elapsed)
+        };
+        let mut stderr = io::stderr().lock();
+        for line in &lines {
+            writeln!(stderr, "{line}")?;
+            self.drawn += height(line, cols);
+        }
+        stderr.flush()
+    }
+
+    /// The checks that are still running, up to [`MAX_LINES`].
+    fn pending_lines(&self, spinner: char, elapsed: u64) -> Vec<String> {
+        let pending = self
+            .checks
+            .iter()
+            .filter(|entry| !entry.done)
+            .collect::<Vec<_>>();
+        let mut lines = pending
+            .iter()
+            .take(MAX_LINES)
+            .map(|entry| {
+                let line = format!("{spinner} {} ({elapsed}s)", entry.description);
+                color::paint("90", line)
+            })
+            .collect::<Vec<_>>();
+        if pending.len() > MAX_LINES {
+            let more = pending.len() - MAX_LINES;
+            lines.push(color::paint("90", format!("  and {more} more")));
+        }
+        lines
+    }
+
+    /// The checks that haven't been printed yet, either with their result or
+    /// a placeholder, as long as they fit in the terminal.
+    fn placeholder_lines(
+        &self,
+        spinner: char,
+        elapsed: u64,
+        cols: usize,
+        rows: usize,
+    ) -> Vec<String> {
+        // One is left for the line that says how many more there are, and
+        // another one for the cursor
+        let max_rows = rows.saturating_sub(2).max(1);
+
+        let mut lines = Vec::new();
+        let mut drawn = 0;
+        for (shown, entry) in self.checks[self.first..].iter().enumerate() {
+            let entry_lines = match (&entry.preview, entry.done) {
+                (Some(preview), _) => preview.lines().map(str::to_owned).collect(),
+                (None, true) => Vec::new(),
+                (None, false) => {
+                    let line = format!("{spinner} {}: running... ({elapsed}s)", entry.description);
+                    vec![color::paint("90", line)]
+                }
+            };
+            let entry_rows = entry_lines
+                .iter()
+                .map(|line| height(line, cols))
+                .sum::<usize>();
+            if drawn + entry_rows > max_rows {
+                let more = self.checks.len() - self.first - shown;
+                lines.push(color::paint("90", format!("  and {more} more")));
+                break;
+            }
+            drawn += entry_rows;
+            lines.extend(entry_lines);
+        }
+        lines
+    }
+
+    /// Removes the pending checks from the screen, so that something else can
+    /// be printed.
+    pub fn clear(&mut self) -> io::Result<()> {
+        if self.drawn == 0 {
+            return Ok(());
+        }
+
+        // Moving the cursor up to the first line and clearing from there
+        let mut stderr = io::stderr().lock();
+        write!(stderr, "\x1b[{}A\x1b[J", self.drawn)?;
+        self.drawn = 0;
+        stderr.flush()
+    }
+}
+
+/// Number of rows the line takes in a terminal that wide, since the long ones
+/// wrap. The escape codes of the colors aren't visible, and tabs move to the
+/// next multiple of 8.
+fn height(line: &str, cols: usize) -> usize {
+    let mut width = 0usize;
+    let mut escape = false;
+    for c in line.chars() {
+        match c {
+            '\x1b' => escape = true,
+            'm' if escape => escape = false,
+            _ if escape => {}
+            '\t' => width = (width / 8 + 1) * 8,
+            _ => width += 1,
+        }
+    }
+    width.div_ceil(cols).max(1)
+}
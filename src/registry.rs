@@ -0,0 +1,1275 @@
+use crate::{
+    aur,
+    cmd::{self, command_exists, CleanupCommand},
+    color,
+    config::{Config, ConfigFile, CustomCheck},
+    privilege, t,
+    users::User,
+};
+
+use std::{error::Error, fmt, str::FromStr};
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+
+/// Packages that provide the programs the commands depend on, for those that
+/// are named differently.
+const PACKAGES: [(&str, &str); 11] = [
+    ("paccache", "pacman-contrib"),
+    ("trash-empty", "trash-cli"),
+    ("mandb", "man-db"),
+    ("locate", "plocate"),
+    ("updatedb", "plocate"),
+    ("systemctl", "systemd"),
+    ("cargo", "rustup"),
+    ("R", "r"),
+    ("yay", "yay from the AUR"),
+    ("dropbox", "dropbox from the AUR"),
+    ("ssh", "openssh"),
+];
+
+/// What a command is about, to filter and group them by.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Packages,
+    Caches,
+    Disk,
+    Development,
+    System,
+}
+
+impl Category {
+    pub const ALL: [Category; 5] = [
+        Category::Packages,
+        Category::Caches,
+        Category::Disk,
+        Category::Development,
+        Category::System,
+    ];
+
+    /// The name in section headers
+    pub fn title(&self) -> &'static str {
+        match self {
+            Category::Packages => "Packages",
+            Category::Caches => "Caches",
+            Category::Disk => "Disk",
+            Category::Development => "Development",
+            Category::System => "System",
+        }
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.title().to_lowercase())
+    }
+}
+
+impl FromStr for Category {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Category::ALL
+            .into_iter()
+            .find(|category| category.to_string() == s)
+            .ok_or_else(|| {
+                format!(
+                    "unknown category '{s}', expected `packages`, `caches`, `disk`, `development` or `system`"
+                )
+            })
+    }
+}
+
+/// A command that can be run, addressed by a stable identifier. It's used to
+/// select commands from the command line and in the configuration file.
+#[derive(Debug)]
+pub struct Entry {
+    pub id: &'static str,
+    pub description: &'static str,
+    /// What the check looks at, for `--explain`
+    pub inspects: &'static str,
+    pub category: Category,
+    /// Whether the command may suggest a fix
+    pub fix: bool,
+    /// What may go wrong when applying the fix, empty if it has none
+    pub risks: &'static str,
+    /// Whether it only inspects the user's home directory, so that it can be
+    /// run for each user with `all-users`
+    pub per_user: bool,
+    /// The external programs it needs
+    pub dependencies: &'static [&'static str],
+    pub new: fn() -> Box<dyn CleanupCommand>,
+}
+
+/// All the available commands, in the order they are run.
+pub const COMMANDS: &[Entry] = &[
+    Entry {
+        id: "last-installed",
+        description: "Last explicitly installed packages, from the pacman log",
+        inspects: "The pacman log in /var/log/pacman.log, for the explicitly installed packages that are still installed.",
+        category: Category::Packages,
+        fix: false,
+        risks: "",
+        per_user: false,
+        dependencies: &["pacman"],
+        new: || Box::new(cmd::LastInstalled),
+    },
+    Entry {
+        id: "orphans",
+        description: "Packages no longer required by any other",
+        inspects: "The local pacman database, for the packages installed as dependencies that no other package needs.",
+        category: Category::Packages,
+        fix: true,
+        risks: "Their dependencies that aren't needed anymore are also uninstalled. Optional dependencies that are in use may be removed too. They can be reinstalled with `arch-clean undo`.",
+        per_user: false,
+        dependencies: &["pacman"],
+        new: || Box::new(cmd::OrphanPackages::default()),
+    },
+    Entry {
+        id: "paccache",
+        description: "Old package versions in the pacman cache",
+        inspects: "The pacman cache in /var/cache/pacman/pkg, with a dry run of `paccache`, also for the uninstalled packages with `purge-uninstalled`.",
+        category: Category::Packages,
+        fix: true,
+        risks: "Only the last `keep` versions of each package are kept, three by default, so older ones can't be downgraded to without downloading them again. With `purge-uninstalled`, the uninstalled packages have to be downloaded again to reinstall them.",
+        per_user: false,
+        dependencies: &["paccache"],
+        new: || Box::new(cmd::Paccache),
+    },
+    Entry {
+        id: "trash",
+        description: "Size of the trash",
+        inspects: "The size of the trash in ~/.local/share/Trash.",
+        category: Category::Disk,
+        fix: true,
+        risks: "The files in the trash are removed for good.",
+        per_user: true,
+        dependencies: &["trash-empty", privilege::DEPENDENCY],
+        new: || Box::new(cmd::TrashSize),
+    },
+    Entry {
+        id: "disk-usage",
+        description: "Largest directories in the home directory",
+        inspects: "The largest files and directories in the home directory, with `du`.",
+        category: Category::Disk,
+        fix: false,
+        risks: "",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::DiskUsage),
+    },
+    Entry {
+        id: "dev-updates",
+        description: "Updates for development packages",
+        inspects: "The development packages from the AUR with new commits, with the AUR helper.",
+        category: Category::Packages,
+        fix: true,
+        risks: "All the packages are upgraded, not only the development ones, and their builds may fail or break.",
+        per_user: false,
+        dependencies: &[aur::DEPENDENCY],
+        new: || Box::new(cmd::DevUpdates::default()),
+    },
+    Entry {
+        id: "nvim-swap",
+        description: "NeoVim swap files",
+        inspects: "The NeoVim swap files in ~/.local/share/nvim/swap.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Unsaved changes of files that are open or were left after a crash are lost.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::NeovimSwapFiles::default()),
+    },
+    Entry {
+        id: "rust-target",
+        description: "Size of Rust target directories",
+        inspects: "The `target` directories of the Cargo projects in the project roots, and `CARGO_TARGET_DIR`.",
+        category: Category::Development,
+        fix: true,
+        risks: "The projects have to be built from scratch again.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::RustTarget::default()),
+    },
+    Entry {
+        id: "man-db",
+        description: "Outdated man-db index",
+        inspects: "The man-db index in /var/cache/man, compared with the last pacman transaction.",
+        category: Category::System,
+        fix: true,
+        risks: "None, the index is only regenerated, which takes a while.",
+        per_user: false,
+        dependencies: &["mandb", privilege::DEPENDENCY],
+        new: || Box::new(cmd::ManDbCache),
+    },
+    Entry {
+        id: "locate-db",
+        description: "Outdated locate database",
+        inspects: "The locate database, and whether the paths pruned in /etc/updatedb.conf are in it.",
+        category: Category::System,
+        fix: true,
+        risks: "None, the database is only regenerated, which takes a while.",
+        per_user: false,
+        dependencies: &["locate", "updatedb", privilege::DEPENDENCY],
+        new: || Box::new(cmd::LocateDb),
+    },
+    Entry {
+        id: "dkms",
+        description: "DKMS modules for removed kernels",
+        inspects: "The DKMS modules built for kernels whose modules aren't in /usr/lib/modules anymore.",
+        category: Category::System,
+        fix: true,
+        risks: "The modules are removed for those kernels, which wouldn't boot with them anyway.",
+        per_user: false,
+        dependencies: &["dkms", privilege::DEPENDENCY],
+        new: || Box::new(cmd::DkmsLeftovers::default()),
+    },
+    Entry {
+        id: "app-leftovers",
+        description: "Configuration and caches of uninstalled applications",
+        inspects: "The directories in ~/.config and ~/.local/share whose name doesn't match any installed package.",
+        category: Category::Disk,
+        fix: true,
+        risks: "The names may not match for applications that are still installed, so each directory is confirmed first.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::AppLeftovers::default()),
+    },
+    Entry {
+        id: "desktop-entries",
+        description: "Desktop entries pointing to missing programs",
+        inspects: "The desktop entries in ~/.local/share/applications and /usr/local/share/applications whose program is missing.",
+        category: Category::System,
+        fix: true,
+        risks: "Entries whose program is installed later, or isn't in the PATH, are removed too.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::BrokenDesktopEntries::default()),
+    },
+    Entry {
+        id: "autostart",
+        description: "Autostart entries for missing programs",
+        inspects: "The entries in ~/.config/autostart whose program is missing or no package owns.",
+        category: Category::System,
+        fix: true,
+        risks: "Each entry is confirmed first, and otherwise only disabled with `Hidden=true`.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::StaleAutostart::default()),
+    },
+    Entry {
+        id: "user-units",
+        description: "systemd user units for missing programs",
+        inspects: "The enabled systemd user units, with `systemctl --user`, whose program is missing.",
+        category: Category::System,
+        fix: true,
+        risks: "The units are disabled, and their files removed when they're in the home directory.",
+        per_user: false,
+        dependencies: &["systemctl"],
+        new: || Box::new(cmd::OrphanUserUnits::default()),
+    },
+    Entry {
+        id: "ollama",
+        description: "Unused Ollama models",
+        inspects: "The Ollama models, with `ollama list`, and when they were last used.",
+        category: Category::Disk,
+        fix: true,
+        risks: "The models have to be downloaded again to use them.",
+        per_user: false,
+        dependencies: &["ollama"],
+        new: || Box::new(cmd::OllamaModels::default()),
+    },
+    Entry {
+        id: "ml-models",
+        description: "Hugging Face and torch hub caches",
+        inspects: "The Hugging Face hub and torch hub caches, for the models and datasets unused in `max-age` days.",
+        category: Category::Caches,
+        fix: true,
+        risks: "The models and datasets have to be downloaded again to use them.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::MlModelCaches::default()),
+    },
+    Entry {
+        id: "zoom",
+        description: "Zoom cache and logs",
+        inspects: "The cache and logs of Zoom in ~/.zoom.",
+        category: Category::Caches,
+        fix: true,
+        risks: "Zoom has to be closed, and its settings are kept.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::ZoomCache::default()),
+    },
+    Entry {
+        id: "thunderbird",
+        description: "Thunderbird folders that need compaction",
+        inspects: "The mail folders of the Thunderbird profiles, for deleted messages that haven't been compacted.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Automatic compaction is enabled in the `user.js` of the profiles, so Thunderbird compacts them without asking from then on, which can't be undone.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::ThunderbirdCompaction::default()),
+    },
+    Entry {
+        id: "syncthing",
+        description: "Old Syncthing file versions",
+        inspects: "The `.stversions` directories of the Syncthing folders, for file versions older than `max-age` days.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Those versions of the files are removed for good from every folder.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::SyncthingVersions::default()),
+    },
+    Entry {
+        id: "dropbox",
+        description: "Dropbox cache",
+        inspects: "The `.dropbox.cache` directory of the Dropbox folder.",
+        category: Category::Caches,
+        fix: true,
+        risks: "The Dropbox client is stopped if it's running, and started again afterwards.",
+        per_user: false,
+        dependencies: &["dropbox"],
+        new: || Box::new(cmd::DropboxCache::default()),
+    },
+    Entry {
+        id: "nextcloud",
+        description: "Nextcloud sync conflicts",
+        inspects: "The synchronized Nextcloud and ownCloud folders, for conflict files.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Conflicts that haven't been resolved yet lose one of their copies, so each file is confirmed first.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::NextcloudConflicts::default()),
+    },
+    Entry {
+        id: "appimages",
+        description: "Outdated AppImage versions",
+        inspects: "The AppImages in ~/Applications and ~/bin, for older versions of the same application.",
+        category: Category::Packages,
+        fix: true,
+        risks: "Only the newest version of each is kept, even if an older one is still needed.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::OutdatedAppImages::default()),
+    },
+    Entry {
+        id: "cups",
+        description: "Old CUPS spool jobs",
+        inspects: "The CUPS spool in /var/spool/cups, for job files older than `max-age` days, and oversized PPD files.",
+        category: Category::System,
+        fix: true,
+        risks: "All the pending and printing jobs are cancelled.",
+        per_user: false,
+        dependencies: &[privilege::DEPENDENCY],
+        new: || Box::new(cmd::CupsSpool),
+    },
+    Entry {
+        id: "audit-logs",
+        description: "Rotated audit logs",
+        inspects: "The rotated audit logs in /var/log/audit, and the retention configured in /etc/audit/auditd.conf.",
+        category: Category::System,
+        fix: true,
+        risks: "The audit trail in the oldest rotated logs, beyond the ones auditd keeps, is lost.",
+        per_user: false,
+        dependencies: &["systemctl", privilege::DEPENDENCY],
+        new: || Box::new(cmd::AuditLogs::default()),
+    },
+    Entry {
+        id: "partial-downloads",
+        description: "Partial downloads in the pacman cache",
+        inspects: "The pacman cache in /var/cache/pacman/pkg, for partial and empty downloads.",
+        category: Category::Packages,
+        fix: true,
+        risks: "Downloads in progress by a running pacman are removed too.",
+        per_user: false,
+        dependencies: &[privilege::DEPENDENCY],
+        new: || Box::new(cmd::PartialDownloads::default()),
+    },
+    Entry {
+        id: "plasma-cache",
+        description: "KDE Plasma cache",
+        inspects: "The KDE Plasma caches in ~/.cache, like ksycoca and the SVG elements.",
+        category: Category::Caches,
+        fix: true,
+        risks: "The caches are regenerated on the next login, which may be slower.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::PlasmaCache::default()),
+    },
+    Entry {
+        id: "session-logs",
+        description: "Oversized X session logs",
+        inspects: "The X session logs in the home directory and ~/.local/share/xorg that are bigger than `max-log-size`.",
+        category: Category::System,
+        fix: true,
+        risks: "The logs of the current session are truncated, losing their contents.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::SessionLogs::default()),
+    },
+    Entry {
+        id: "crash-logs",
+        description: "Old crash and debug logs",
+        inspects: "The crash dumps and debug logs in the home directory and the project roots.",
+        category: Category::System,
+        fix: true,
+        risks: "The information to debug those crashes is lost.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::CrashLogs::default()),
+    },
+    Entry {
+        id: "lutris",
+        description: "Unused Lutris runner versions",
+        inspects: "The Wine, DXVK and VKD3D versions installed by Lutris that no game or runner configuration uses.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Games configured outside of Lutris with those versions would stop working.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::LutrisRunners::default()),
+    },
+    Entry {
+        id: "completion-dumps",
+        description: "Stale shell completion dumps",
+        inspects: "The zsh completion dumps for other zsh versions, and oversized bash completion caches.",
+        category: Category::System,
+        fix: true,
+        risks: "None, they're regenerated by the shell when needed.",
+        per_user: true,
+        dependencies: &["zsh"],
+        new: || Box::new(cmd::CompletionDumps::default()),
+    },
+    Entry {
+        id: "local-state",
+        description: "State of uninstalled applications in ~/.local/state",
+        inspects: "The application state in ~/.local/state, for uninstalled applications and oversized histories and logs.",
+        category: Category::Disk,
+        fix: true,
+        risks: "Histories and state that are still wanted are lost, so each entry is confirmed first.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::LocalState::default()),
+    },
+    Entry {
+        id: "cargo-bins",
+        description: "Binaries installed with cargo",
+        inspects: "The binaries installed with `cargo install`, and whether they're outdated or unused.",
+        category: Category::Packages,
+        fix: true,
+        risks: "Outdated binaries are built again from source, and each unused one is confirmed before uninstalling it.",
+        per_user: false,
+        dependencies: &["cargo"],
+        new: || Box::new(cmd::CargoBinaries::default()),
+    },
+    Entry {
+        id: "ml-caches",
+        description: "Machine learning tool caches",
+        inspects: "The Keras, torch extensions and Weights & Biases caches, and the TensorBoard and Lightning logs of the projects.",
+        category: Category::Caches,
+        fix: true,
+        risks: "The logs of old experiments are lost, and the caches have to be downloaded or built again.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::MlToolCaches::default()),
+    },
+    Entry {
+        id: "sdkman",
+        description: "Old SDKMAN versions",
+        inspects: "The SDKMAN candidates, for the versions that aren't the current one.",
+        category: Category::Development,
+        fix: true,
+        risks: "Projects that pin those versions would have to install them again.",
+        per_user: false,
+        dependencies: &["bash"],
+        new: || Box::new(cmd::SdkmanVersions::default()),
+    },
+    Entry {
+        id: "dev-containers",
+        description: "Unused development containers and images",
+        inspects: "The distrobox and toolbox containers, with `podman`, and when they were last entered.",
+        category: Category::Development,
+        fix: true,
+        risks: "Everything inside the containers that isn't in the home directory is lost.",
+        per_user: false,
+        dependencies: &["podman"],
+        new: || Box::new(cmd::DevContainers::default()),
+    },
+    Entry {
+        id: "waydroid",
+        description: "Waydroid data and images",
+        inspects: "The Waydroid images and data in /var/lib/waydroid and ~/.local/share/waydroid, for the data of the apps that `waydroid shell pm list packages` doesn't list, which needs root and a running session.",
+        category: Category::Disk,
+        fix: true,
+        risks: "The data of the removed apps is lost.",
+        per_user: false,
+        dependencies: &["waydroid", privilege::DEPENDENCY],
+        new: || Box::new(cmd::WaydroidData::default()),
+    },
+    Entry {
+        id: "boot-entries",
+        description: "systemd-boot entries for removed kernels",
+        inspects: "The systemd-boot entries in the EFI system partition whose kernel or initramfs is missing.",
+        category: Category::System,
+        fix: true,
+        risks: "Entries for kernels in other partitions would be removed too.",
+        per_user: false,
+        dependencies: &[privilege::DEPENDENCY],
+        new: || Box::new(cmd::StaleBootEntries::default()),
+    },
+    Entry {
+        id: "pacman-hooks",
+        description: "pacman hooks for missing programs",
+        inspects: "The pacman hooks in /etc/pacman.d/hooks whose program is missing.",
+        category: Category::System,
+        fix: true,
+        risks: "Each hook is confirmed first, and otherwise only disabled by renaming it.",
+        per_user: false,
+        dependencies: &[privilege::DEPENDENCY],
+        new: || Box::new(cmd::OrphanPacmanHooks::default()),
+    },
+    Entry {
+        id: "python-artifacts",
+        description: "Python build artifacts",
+        inspects: "The build, dist, egg-info and tox directories of the Python projects in the project roots.",
+        category: Category::Development,
+        fix: true,
+        risks: "The projects have to be built again, and `dist` may contain releases that aren't published anywhere else.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::PythonArtifacts::default()),
+    },
+    Entry {
+        id: "js-builds",
+        description: "JavaScript build outputs",
+        inspects: "The build outputs of the JavaScript projects in the project roots, like .next or dist.",
+        category: Category::Development,
+        fix: true,
+        risks: "The projects have to be built again, so each one is confirmed first.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::JsBuildOutputs::default()),
+    },
+    Entry {
+        id: "gradle-builds",
+        description: "Gradle project build directories",
+        inspects: "The build directories of the Gradle projects in the project roots older than `max-age` days.",
+        category: Category::Development,
+        fix: true,
+        risks: "The projects have to be built again.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::GradleBuilds::default()),
+    },
+    Entry {
+        id: "engine-caches",
+        description: "Game engine project caches",
+        inspects: "The Library, Intermediate and DerivedDataCache directories of the Unity and Unreal projects in the project roots.",
+        category: Category::Development,
+        fix: true,
+        risks: "The engines regenerate them when opening the project, which may take long.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::GameEngineCaches::default()),
+    },
+    Entry {
+        id: "docker-logs",
+        description: "Oversized Docker container logs",
+        inspects: "The logs of the Docker containers bigger than `max-log-size`, and the log rotation in /etc/docker/daemon.json.",
+        category: Category::System,
+        fix: true,
+        risks: "The logs of the containers are truncated, losing their contents.",
+        per_user: false,
+        dependencies: &["docker", privilege::DEPENDENCY],
+        new: || Box::new(cmd::DockerLogs::default()),
+    },
+    Entry {
+        id: "browser-downloads",
+        description: "Playwright and Puppeteer browsers",
+        inspects: "The browsers downloaded by Playwright and Puppeteer, and which versions the projects, the global npm packages in /usr/lib/node_modules and the Python packages use.",
+        category: Category::Development,
+        fix: true,
+        risks: "Projects not in the project roots may need the browsers removed, and download them again.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::BrowserDownloads::default()),
+    },
+    Entry {
+        id: "cypress",
+        description: "Cypress binary cache",
+        inspects: "The Cypress binary cache, and which versions the projects in the project roots use.",
+        category: Category::Development,
+        fix: true,
+        risks: "Projects not in the project roots may need the versions removed, and download them again.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::CypressCache::default()),
+    },
+    Entry {
+        id: "deno",
+        description: "Deno cache",
+        inspects: "The Deno cache, and the projects in the project roots with a lock file.",
+        category: Category::Development,
+        fix: true,
+        risks: "The dependencies are downloaded again for the active projects, and anything else that uses the cache.",
+        per_user: false,
+        dependencies: &["deno"],
+        new: || Box::new(cmd::DenoCache::default()),
+    },
+    Entry {
+        id: "bun",
+        description: "Bun cache",
+        inspects: "The Bun install cache and the bunx caches.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages are downloaded again the next time they're installed.",
+        per_user: false,
+        dependencies: &["bun"],
+        new: || Box::new(cmd::BunCache::default()),
+    },
+    Entry {
+        id: "pub-cache",
+        description: "Flutter and Dart pub cache",
+        inspects: "The Dart pub cache and the artifacts of the Flutter SDKs that no project uses.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages are downloaded again, and the unused SDKs have to fetch their artifacts again.",
+        per_user: false,
+        dependencies: &["dart"],
+        new: || Box::new(cmd::PubCache::default()),
+    },
+    Entry {
+        id: "ruby-bundles",
+        description: "Vendored Ruby bundles",
+        inspects: "The gems vendored with Bundler in the Ruby projects in the project roots, untouched in `max-age` days.",
+        category: Category::Development,
+        fix: true,
+        risks: "The gems have to be installed again with `bundle install`.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::RubyBundles::default()),
+    },
+    Entry {
+        id: "elixir",
+        description: "Elixir mix and hex caches",
+        inspects: "The hex and mix caches, and the _build and deps directories of the Elixir projects in the project roots.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages are downloaded again and the projects built from scratch.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::ElixirCaches::default()),
+    },
+    Entry {
+        id: "zig",
+        description: "Zig caches",
+        inspects: "The global Zig cache and the caches and outputs of the Zig projects in the project roots.",
+        category: Category::Development,
+        fix: true,
+        risks: "The projects have to be built again from scratch.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::ZigCache::default()),
+    },
+    Entry {
+        id: "opam",
+        description: "Unused opam switches",
+        inspects: "The opam switches, with `opam switch list`, and which projects use them.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages of the removed switches have to be built again to use them.",
+        per_user: false,
+        dependencies: &["opam"],
+        new: || Box::new(cmd::OpamSwitches::default()),
+    },
+    Entry {
+        id: "cpp-caches",
+        description: "Conan and vcpkg caches",
+        inspects: "The Conan cache, and the vcpkg packages, build trees and binary cache.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages are built or downloaded again the next time they're needed.",
+        per_user: false,
+        dependencies: &["conan"],
+        new: || Box::new(cmd::CppPackageCaches::default()),
+    },
+    Entry {
+        id: "scala-caches",
+        description: "Scala sbt, Ivy and Coursier caches",
+        inspects: "The Coursier and Ivy caches, and the sbt boot and zinc directories, for artifact versions not resolved in `max-age` days. Nothing is flagged on `noatime` mounts.",
+        category: Category::Development,
+        fix: true,
+        risks: "The artifacts are downloaded again the next time a project needs them.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::ScalaCaches::default()),
+    },
+    Entry {
+        id: "r-libraries",
+        description: "R user libraries for old versions",
+        inspects: "The R user libraries in the home directory for R versions that aren't installed, and the leftover temporary downloads.",
+        category: Category::Development,
+        fix: true,
+        risks: "The packages have to be installed again to use those R versions.",
+        per_user: true,
+        dependencies: &["R"],
+        new: || Box::new(cmd::RLibraries::default()),
+    },
+    Entry {
+        id: "godot",
+        description: "Godot project import caches",
+        inspects: "The .godot and .import directories of the Godot projects in the project roots.",
+        category: Category::Development,
+        fix: true,
+        risks: "The editor imports the assets again when opening the project, which may take long.",
+        per_user: true,
+        dependencies: &[],
+        new: || Box::new(cmd::GodotImports::default()),
+    },
+];
+
+/// A command selected to run, either built-in or defined by the user.
+pub struct Check {
+    pub id: String,
+    pub description: String,
+    /// Only the custom commands may have none
+    pub category: Option<Category>,
+    pub dependencies: &'static [&'static str],
+    pub cmd: Box<dyn CleanupCommand>,
+    /// The user whose home directory is checked, if it's not the invoking one
+    pub user: Option<User>,
+    /// The machine it's run on over SSH, if it's not this one
+    pub host: Option<String>,
+}
+
+impl Check {
+    fn builtin(entry: &Entry) -> Check {
+        Check {
+            id: entry.id.to_string(),
+            description: entry.description.to_string(),
+            category: Some(entry.category),
+            dependencies: entry.dependencies,
+            cmd: (entry.new)(),
+            user: None,
+            host: None,
+        }
+    }
+
+    fn custom(custom: &CustomCheck) -> Check {
+        Check {
+            id: custom.id.clone(),
+            description: custom.description(),
+            category: custom.category,
+            dependencies: &[],
+            cmd: Box::new(cmd::Custom::new(custom.clone())),
+            user: None,
+            host: None,
+        }
+    }
+
+    /// The first dependency that isn't installed, in which case the check is
+    /// skipped instead of failing.
+    pub fn missing_dependency(&self, config: &Config) -> Option<MissingDependency> {
+        let dep = self
+            .dependencies
+            .iter()
+            .find(|dep| !dependency_installed(dep, config))?;
+        let program = dep.split('|').next().unwrap_or(dep);
+        let package = PACKAGES
+            .iter()
+            .find(|(name, _)| *name == program)
+            .map_or(program, |(_, package)| package);
+
+        Some(MissingDependency {
+            program: dep.to_string(),
+            package: package.to_string(),
+        })
+    }
+}
+
+/// The reason a check was skipped, as its error.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct MissingDependency {
+    pub program: String,
+    /// The package that provides it, as a suggestion
+    pub package: String,
+}
+
+impl fmt::Display for MissingDependency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let msg = t!(
+            "requires {program}, install {package}",
+            program = self.program,
+            package = self.package
+        );
+        f.write_str(&msg)
+    }
+}
+
+impl Error for MissingDependency {}
+
+/// Whether a dependency is installed. Alternatives are separated by `|`, and
+/// may be overridden in the configuration.
+fn dependency_installed(dep: &str, config: &Config) -> bool {
+    let configured = match dep {
+        aur::DEPENDENCY => config.aur_helper.as_deref(),
+        privilege::DEPENDENCY if privilege::is_root() => return true,
+        privilege::DEPENDENCY => config.root_command.as_deref(),
+        _ => None,
+    };
+    match configured {
+        Some(program) => command_exists(program),
+        None => dep.split('|').any(command_exists),
+    }
+}
+
+/// The sets of commands that make sense for each kind of machine. The custom
+/// commands are always included, since they can't be known here.
+const PROFILES: [(&str, &[&str]); 3] = [
+    (
+        "desktop",
+        &[
+            "last-installed",
+            "orphans",
+            "paccache",
+            "trash",
+            "disk-usage",
+            "dev-updates",
+            "nvim-swap",
+            "man-db",
+            "locate-db",
+            "dkms",
+            "app-leftovers",
+            "desktop-entries",
+            "autostart",
+            "user-units",
+            "zoom",
+            "thunderbird",
+            "syncthing",
+            "dropbox",
+            "nextcloud",
+            "appimages",
+            "cups",
+            "partial-downloads",
+            "plasma-cache",
+            "session-logs",
+            "crash-logs",
+            "lutris",
+            "completion-dumps",
+            "local-state",
+            "waydroid",
+            "boot-entries",
+            "pacman-hooks",
+        ],
+    ),
+    (
+        "server",
+        &[
+            "last-installed",
+            "orphans",
+            "paccache",
+            "disk-usage",
+            "man-db",
+            "locate-db",
+            "dkms",
+            "user-units",
+            "audit-logs",
+            "partial-downloads",
+            "crash-logs",
+            "boot-entries",
+            "pacman-hooks",
+            "docker-logs",
+        ],
+    ),
+    (
+        "developer",
+        &[
+            "orphans",
+            "paccache",
+            "disk-usage",
+            "dev-updates",
+            "nvim-swap",
+            "rust-target",
+            "ollama",
+            "ml-models",
+            "cargo-bins",
+            "ml-caches",
+            "sdkman",
+            "dev-containers",
+            "python-artifacts",
+            "js-builds",
+            "gradle-builds",
+            "engine-caches",
+            "docker-logs",
+            "browser-downloads",
+            "cypress",
+            "deno",
+            "bun",
+            "pub-cache",
+            "ruby-bundles",
+            "elixir",
+            "zig",
+            "opam",
+            "cpp-caches",
+            "scala-caches",
+            "r-libraries",
+            "godot",
+        ],
+    ),
+];
+
+/// The commands in a profile, and whether it restricts the custom commands
+/// too, which only the ones in the configuration do. Those also take
+/// precedence over the built-in ones.
+fn profile<'a>(name: &str, file: &'a ConfigFile) -> Result<(Vec<&'a str>, bool)> {
+    if let Some(ids) = file.profiles.get(name) {
+        for id in ids {
+            let builtin = COMMANDS.iter().any(|entry| entry.id == id);
+            if !builtin && !file.custom.iter().any(|custom| custom.id == *id) {
+                bail!("unknown command '{id}' in profile '{name}', see `arch-clean list`");
+            }
+        }
+        return Ok((ids.iter().map(String::as_str).collect(), true));
+    }
+
+    match PROFILES.iter().find(|(profile, _)| *profile == name) {
+        Some((_, ids)) => Ok((ids.to_vec(), false)),
+        None => bail!("unknown profile '{name}', see `arch-clean list`"),
+    }
+}
+
+/// Parses a comma-separated list of identifiers, making sure they exist.
+pub fn parse_ids<'a>(list: &'a str, custom: &[CustomCheck]) -> Result<Vec<&'a str>> {
+    let ids: Vec<_> = list.split(',').map(str::trim).collect();
+    for id in &ids {
+        let builtin = COMMANDS.iter().any(|entry| entry.id == *id);
+        if !builtin && !custom.iter().any(|custom| custom.id == *id) {
+            bail!("unknown command '{id}', see `arch-clean list`");
+        }
+    }
+
+    Ok(ids)
+}
+
+/// Parses a comma-separated list of categories.
+fn parse_categories(list: &str) -> Result<Vec<Category>> {
+    list.split(',')
+        .map(|category| category.trim().parse().map_err(anyhow::Error::msg))
+        .collect()
+}
+
+/// The commands to run, optionally with only the ones in `only`, in the
+/// profile or in the comma-separated `categories`, and without the ones in
+/// `skip`. The custom commands are run after the built-in ones.
+pub fn select(
+    only: Option<&str>,
+    skip: Option<&str>,
+    profile: Option<&str>,
+    categories: Option<&str>,
+    file: &ConfigFile,
+) -> Result<Vec<Check>> {
+    let custom = &file.custom;
+    for custom in custom {
+        if COMMANDS.iter().any(|entry| entry.id == custom.id) {
+            bail!(
+                "custom command '{}' has the same name as a built-in one",
+                custom.id
+            );
+        }
+    }
+
+    let only = only.map(|only| parse_ids(only, custom)).transpose()?;
+    let skip = skip
+        .map(|skip| parse_ids(skip, custom))
+        .transpose()?
+        .unwrap_or_default();
+    let profile = profile.map(|name| self::profile(name, file)).transpose()?;
+    let categories = categories.map(parse_categories).transpose()?;
+    let included = |check: &Check, custom: bool| {
+        let id = check.id.as_str();
+        let in_profile = match &profile {
+            Some((ids, restricts_custom)) => (custom && !restricts_custom) || ids.contains(&id),
+            None => true,
+        };
+        let in_categories = categories.as_ref().is_none_or(|categories| {
+            check
+                .category
+                .is_some_and(|category| categories.contains(&category))
+        });
+        in_profile
+            && in_categories
+            && only.as_ref().is_none_or(|only| only.contains(&id))
+            && !skip.contains(&id)
+    };
+
+    let builtins = COMMANDS
+        .iter()
+        .map(Check::builtin)
+        .filter(|check| included(check, false));
+    let custom = custom
+        .iter()
+        .map(Check::custom)
+        .filter(|check| included(check, true));
+    Ok(builtins.chain(custom).collect())
+}
+
+/// The checks with the ones that only inspect the home directory repeated for
+/// each of the users, in the same order. The rest are kept as they are.
+pub fn for_users(checks: Vec<Check>, users: &[User]) -> Vec<Check> {
+    checks
+        .into_iter()
+        .flat_map(|check| {
+            match COMMANDS
+                .iter()
+                .find(|entry| entry.id == check.id && entry.per_user)
+            {
+                Some(entry) => users
+                    .iter()
+                    .map(|user| Check {
+                        user: Some(user.clone()),
+                        ..Check::builtin(entry)
+                    })
+                    .collect(),
+                None => vec![check],
+            }
+        })
+        .collect()
+}
+
+/// The identifiers of all the commands with their description, including
+/// the custom ones.
+pub fn ids(file: &ConfigFile) -> Vec<(String, String)> {
+    let builtins = COMMANDS
+        .iter()
+        .map(|entry| (entry.id.to_owned(), entry.description.to_owned()));
+    let custom = file
+        .custom
+        .iter()
+        .map(|custom| (custom.id.clone(), custom.description()));
+    builtins.chain(custom).collect()
+}
+
+/// The names of all the profiles, including the custom ones.
+pub fn profiles(file: &ConfigFile) -> Vec<String> {
+    let builtins = PROFILES
+        .iter()
+        .map(|(name, _)| name.to_string())
+        .filter(|name| !file.profiles.contains_key(name));
+    let mut custom = file.profiles.keys().cloned().collect::<Vec<_>>();
+    custom.sort();
+    builtins.chain(custom).collect()
+}
+
+/// Prints a dependency with its status.
+fn dependency_status(dep: &str) -> String {
+    // Alternatives are separated by `|`, of which the installed one is shown
+    match dep.split('|').find(|dep| command_exists(dep)) {
+        Some(dep) => color::paint("32", dep),
+        None => color::paint("31", format!("{dep} (missing)")),
+    }
+}
+
+/// Prints what the command inspects, the programs it requires and whether they
+/// are installed, and what applying its fix risks, to audit it before running
+/// it. Like [`list`], the descriptions are in English. Returns whether it may
+/// suggest a fix.
+pub fn explain(id: &str, file: &ConfigFile) -> Result<bool> {
+    let title = |category: Option<Category>, description: &str| {
+        let category = category.map_or("custom".to_string(), |c| c.to_string());
+        println!("{} ({category}): {description}", color::paint("36;1", id));
+    };
+
+    if let Some(entry) = COMMANDS.iter().find(|entry| entry.id == id) {
+        title(Some(entry.category), entry.description);
+        println!("  inspects: {}", entry.inspects);
+        if !entry.dependencies.is_empty() {
+            let deps = entry
+                .dependencies
+                .iter()
+                .map(|dep| dependency_status(dep))
+                .collect::<Vec<_>>()
+                .join(", ");
+            println!("  requires: {deps}");
+        }
+        if entry.fix {
+            println!("  risks: {}", entry.risks);
+        } else {
+            println!("  fix: none, it only reports");
+        }
+        return Ok(entry.fix);
+    }
+
+    let Some(custom) = file.custom.iter().find(|custom| custom.id == id) else {
+        bail!("unknown command '{id}', see `arch-clean list`");
+    };
+    title(custom.category, &custom.description());
+    println!("  runs: {}", custom.check);
+    if let Some(program) = custom.check.split_whitespace().next() {
+        println!("  requires: {}", dependency_status(program));
+    }
+    match &custom.fix {
+        Some(fix) => {
+            println!("  fix: {fix}");
+            println!("  risks: defined in the configuration, review the fix above");
+        }
+        None => println!("  fix: none, it only reports"),
+    }
+
+    Ok(custom.fix.is_some())
+}
+
+/// Prints all the available commands by category, and whether their
+/// dependencies are installed, followed by the profiles.
+pub fn list(file: &ConfigFile) {
+    let custom = &file.custom;
+    for category in Category::ALL {
+        println!("{}", color::paint("36;1", format!("{}:", category.title())));
+        for entry in COMMANDS.iter().filter(|entry| entry.category == category) {
+            let fix = if entry.fix { " (fix available)" } else { "" };
+            println!(
+                "  {}{fix}: {}",
+                color::paint("36;1", entry.id),
+                entry.description
+            );
+
+            if !entry.dependencies.is_empty() {
+                let deps = entry
+                    .dependencies
+                    .iter()
+                    .map(|dep| dependency_status(dep))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                println!("    requires: {deps}");
+            }
+        }
+        println!();
+    }
+
+    if !custom.is_empty() {
+        println!("{}", color::paint("36;1", "Custom:"));
+        for custom in custom {
+            let fix = if custom.fix.is_some() {
+                " (fix available)"
+            } else {
+                ""
+            };
+            println!(
+                "  {}{fix}: {}",
+                color::paint("36;1", &custom.id),
+                custom.description()
+            );
+            if let Some(category) = custom.category {
+                println!("    category: {category}");
+            }
+            if let Some(program) = custom.check.split_whitespace().next() {
+                println!("    requires: {}", dependency_status(program));
+            }
+        }
+        println!();
+    }
+    println!("{}", color::paint("36;1", "Profiles:"));
+    for (name, ids) in PROFILES {
+        if !file.profiles.contains_key(name) {
+            println!("  {name}: {}", ids.join(", "));
+        }
+    }
+    let mut custom_profiles = file.profiles.iter().collect::<Vec<_>>();
+    custom_profiles.sort();
+    for (name, ids) in custom_profiles {
+        println!("  {name}: {} (custom)", ids.join(", "));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+[[custom]]
+id = "docker-images"
+check = "docker images --quiet --filter dangling=true"
+"#;
+
+    fn ids(checks: &[Check]) -> Vec<&str> {
+        checks.iter().map(|check| check.id.as_str()).collect()
+    }
+
+    #[test]
+    fn parse_known_ids() {
+        let file = toml::from_str::<ConfigFile>(CONFIG).unwrap();
+        assert_eq!(
+            parse_ids("orphans, trash,docker-images", &file.custom).unwrap(),
+            ["orphans", "trash", "docker-images"]
+        );
+        assert_eq!(
+            parse_ids("orphans,orphans", &file.custom).unwrap(),
+            ["orphans", "orphans"]
+        );
+    }
+
+    #[test]
+    fn parse_unknown_ids() {
+        let file = toml::from_str::<ConfigFile>(CONFIG).unwrap();
+        let err = parse_ids("orphans,nope", &file.custom).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown command 'nope', see `arch-clean list`"
+        );
+        assert!(parse_ids("orphans,", &file.custom).is_err());
+        assert!(parse_ids("docker-images", &[]).is_err());
+    }
+
+    #[test]
+    fn select_only_and_skip() {
+        let file = toml::from_str::<ConfigFile>(CONFIG).unwrap();
+        // Each command runs once, in the order of the registry and with the
+        // custom ones last
+        let checks = select(
+            Some("docker-images,trash,orphans,trash"),
+            None,
+            None,
+            None,
+            &file,
+        )
+        .unwrap();
+        assert_eq!(ids(&checks), ["orphans", "trash", "docker-images"]);
+
+        let checks = select(
+            Some("orphans,trash,docker-images"),
+            Some("trash,docker-images"),
+            None,
+            None,
+            &file,
+        )
+        .unwrap();
+        assert_eq!(ids(&checks), ["orphans"]);
+
+        let checks = select(None, Some("orphans"), None, None, &file).unwrap();
+        assert_eq!(checks.len(), COMMANDS.len());
+        assert!(!ids(&checks).contains(&"orphans"));
+        assert!(ids(&checks).contains(&"docker-images"));
+
+        assert!(select(None, Some("nope"), None, None, &file).is_err());
+    }
+
+    #[test]
+    fn select_with_categories() {
+        let file = toml::from_str::<ConfigFile>(CONFIG).unwrap();
+        let checks = select(None, Some("paccache"), None, Some("packages"), &file).unwrap();
+        assert!(!checks.is_empty());
+        assert!(checks
+            .iter()
+            .all(|check| check.category == Some(Category::Packages)));
+        assert!(!ids(&checks).contains(&"paccache"));
+        // The custom commands have no category unless given one
+        assert!(!ids(&checks).contains(&"docker-images"));
+
+        let checks = select(Some("orphans,trash"), None, None, Some("disk"), &file).unwrap();
+        assert_eq!(ids(&checks), ["trash"]);
+    }
+}
@@ -0,0 +1,553 @@
+//! The frontends of a run implement [`Renderer`], which receives what happens
+//! as [`Event`]s. [`run`] drives the checks and the fixes, so that the
+//! frontends only decide how the results are shown and which fixes are
+//! applied.
+
+use crate::report::Report;
+
+use arch_clean::{
+    cmd::{self, FixResult},
+    color, i18n,
+    registry::{Category, Check},
+    runner::CheckResult,
+    t, undo, Runner,
+};
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    path::PathBuf,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use tokio::{
+    process::Command,
+    signal,
+    sync::mpsc,
+    time::{self, MissedTickBehavior},
+};
+
+/// Something that happened during a run
+pub enum Event<'a> {
+    /// The checks are about to run
+    Started(&'a [Check]),
+    /// Sent periodically while the checks run, e.g., to animate them
+    Tick,
+    /// With `group`, the results are sent in the same order as the checks,
+    /// which are sorted by category
+    CheckFinished(&'a CheckResult),
+    /// The fix was confirmed and is about to be applied
+    FixStarted(&'a CheckResult),
+    /// With the outcome of the fix
+    FixApplied(&'a Result<FixResult>),
+    /// Ctrl-C was pressed while applying a fix, so the run stops right away
+    Interrupted(&'a Interruption),
+    /// Everything is done, including the fixes
+    Finished(&'a Summary),
+}
+
+/// A frontend for the results of a run.
+pub trait Renderer {
+    fn render(&mut self, event: Event<'_>) -> Result<()>;
+
+    /// Whether the fix of a result should be applied right after it's
+    /// rendered. It's only asked for the results with a fix available.
+    fn confirm(&mut self, _result: &CheckResult) -> Result<bool> {
+        Ok(false)
+    }
+
+    /// Whether to stop waiting for the rest of the checks, e.g., because the
+    /// user is done with them.
+    fn stopped(&self) -> bool {
+        false
+    }
+
+    /// The fixes to apply once the checks are done, by the position of their
+    /// check, for the frontends that pick all of them at once.
+    fn selected(&mut self) -> Result<Vec<usize>> {
+        Ok(Vec::new())
+    }
+}
+
+/// Statistics about the whole run, printed at the end
+#[derive(Default)]
+pub struct Summary {
+    checks: usize,
+    failed: usize,
+    skipped: usize,
+    fixes: usize,
+    reclaimable_bytes: u64,
+    /// The same for each of the hosts, if they were run on other machines
+    hosts: BTreeMap<String, Summary>,
+    /// What the fixes did in total, if any was applied
+    fixed: Option<FixResult>,
+    /// The checks and fixes that failed, in the order they did
+    failures: Vec<Failure>,
+}
+
+/// A check or a fix that failed, shown at the end of the run instead of along
+/// with the results.
+pub struct Failure {
+    /// See [`CheckResult::key`]
+    id: String,
+    /// Whether it was the fix that failed, rather than the check
+    fix: bool,
+    /// With all of its causes
+    error: String,
+}
+
+impl Failure {
+    fn new(result: &CheckResult, fix: bool, error: &anyhow::Error) -> Self {
+        Failure {
+            id: result.key(),
+            fix,
+            error: format!("{error:#}"),
+        }
+    }
+}
+
+impl Summary {
+    pub fn new(results: &[CheckResult]) -> Summary {
+        let mut summary = Summary::default();
+        results.iter().for_each(|result| summary.add(result));
+        summary
+    }
+
+    fn add(&mut self, result: &CheckResult) {
+        if let Some(host) = &result.host {
+            self.hosts.entry(host.clone()).or_default().add_own(result);
+        }
+        self.add_own(result);
+        if let (Err(e), None) = (&result.output, result.skipped()) {
+            self.failures.push(Failure::new(result, false, e));
+        }
+    }
+
+    fn add_own(&mut self, result: &CheckResult) {
+        self.checks += 1;
+        match &result.output {
+            Err(_) if result.skipped().is_some() => self.skipped += 1,
+            Err(_) => self.failed += 1,
+            Ok(out) if out.fix_available => {
+                self.fixes += 1;
+                self.reclaimable_bytes += out.reclaimable_bytes.unwrap_or(0);
+            }
+            Ok(_) => {}
+        }
+    }
+
+    /// In a few words, e.g. `4 cleanups available, ~6.0 GiB reclaimable`.
+    pub fn short(&self) -> String {
+        let mut short = match self.fixes {
+            0 => t!("Nothing to clean up"),
+            1 => t!("1 cleanup available"),
+            fixes => t!("{fixes} cleanups available", fixes = fixes),
+        };
+        if self.reclaimable_bytes > 0 {
+            let size = cmd::human_size(self.reclaimable_bytes);
+            short += &t!(", ~{size} reclaimable", size = size);
+        }
+        if self.failed > 0 {
+            short += &t!(" ({failed} failed)", failed = self.failed);
+        }
+
+        short
+    }
+
+    /// How much the fixes freed, if any was applied and it's known, e.g.
+    /// `Freed 4.2 GiB in total`.
+    pub fn fixed_line(&self) -> Option<String> {
+        let bytes = self.fixed.as_ref()?.freed_bytes?;
+        let size = cmd::human_size(bytes);
+        Some(color::paint(
+            "32;1",
+            t!("Freed {size} in total", size = size),
+        ))
+    }
+
+    /// Whether any check or fix failed, e.g., to exit with an error.
+    pub fn failed(&self) -> bool {
+        !self.failures.is_empty()
+    }
+
+    /// The errors of the checks and fixes that failed, by their command, if
+    /// any did.
+    pub fn failures(&self) -> Option<String> {
+        if self.failures.is_empty() {
+            return None;
+        }
+
+        let mut section = format!("{}\n", color::paint("31;1", t!("Failures:")));
+        for failure in &self.failures {
+            let id = if failure.fix {
+                t!("{id} (fix)", id = failure.id)
+            } else {
+                failure.id.clone()
+            };
+            section += &format!("  {}: {}\n", color::paint("31", id), failure.error);
+        }
+        Some(section)
+    }
+
+    /// Sends it as a desktop notification with `notify-send`.
+    pub async fn notify(&self) -> Result<()> {
+        let status = Command::new("notify-send")
+            .arg("--app-name=arch-clean")
+            .arg("arch-clean")
+            .arg(self.short())
+            .status()
+            .await
+            .context("couldn't run notify-send, install libnotify")?;
+        if !status.success() {
+            bail!("notify-send failed with {status}");
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", color::paint("36;1", t!("Summary:")))?;
+        write!(f, "{}", t!("{checks} checks run", checks = self.checks))?;
+        match (self.failed, self.skipped) {
+            (0, 0) => {}
+            (failed, 0) => write!(f, "{}", t!(" ({failed} failed)", failed = failed))?,
+            (0, skipped) => write!(f, "{}", t!(" ({skipped} skipped)", skipped = skipped))?,
+            (failed, skipped) => write!(
+                f,
+                "{}",
+                t!(
+                    " ({failed} failed, {skipped} skipped)",
+                    failed = failed,
+                    skipped = skipped
+                )
+            )?,
+        }
+        writeln!(
+            f,
+            "{}",
+            t!(", {fixes} with fixes available", fixes = self.fixes)
+        )?;
+        let size = cmd::human_size(self.reclaimable_bytes);
+        writeln!(
+            f,
+            "{}",
+            t!(
+                "About {size} can be freed by applying all of them",
+                size = size
+            )
+        )?;
+        for (host, summary) in &self.hosts {
+            writeln!(f, "  {host}: {}", summary.short())?;
+        }
+        if let Some(line) = self.fixed_line() {
+            writeln!(f, "{line}")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What was done when the fixes were interrupted
+pub struct Interruption {
+    /// The command whose fix was cancelled
+    pub id: String,
+    /// The directories that were left partially removed
+    pub partial: Vec<PathBuf>,
+    pub applied: Vec<String>,
+    /// The commands with a fix available that wasn't applied, including the
+    /// one that was cancelled
+    pub not_applied: Vec<String>,
+}
+
+impl fmt::Display for Interruption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", color::paint("31;1", t!("Interrupted:")))?;
+        write!(f, "{}", t!("The fix of {id} was cancelled", id = self.id))?;
+        if self.partial.is_empty() {
+            writeln!(f)?;
+        } else {
+            writeln!(f, "{}", t!(", leaving these partially removed:"))?;
+            for path in &self.partial {
+                writeln!(f, "  {}", path.display())?;
+            }
+        }
+
+        let list = |ids: &[String]| match ids {
+            [] => t!("none"),
+            ids => ids.join(", "),
+        };
+        writeln!(f, "{}", t!("Applied: {ids}", ids = list(&self.applied)))?;
+        writeln!(
+            f,
+            "{}",
+            t!("Not applied: {ids}", ids = list(&self.not_applied))
+        )
+    }
+}
+
+/// Prints how applying a fix went, e.g. `Done: freed 1.2 GiB, 3 removed`. The
+/// errors are shown at the end of the run, see [`Summary::failures`].
+pub fn print_outcome(outcome: &Result<FixResult>) {
+    match outcome {
+        Err(_) => println!("{}\n", color::paint("31", t!("Failed"))),
+        Ok(fixed) => {
+            // Some of its items may have failed
+            let code = if fixed.failures.is_empty() {
+                "32"
+            } else {
+                "33"
+            };
+            let fixed = fixed.to_string();
+            let done = if fixed.is_empty() {
+                t!("Done")
+            } else {
+                t!("Done: {result}", result = fixed)
+            };
+            println!("{}\n", color::paint(code, done));
+        }
+    }
+}
+
+/// The header of a category in the grouped output, for the results without
+/// one too.
+pub fn category_header(category: Option<Category>) -> String {
+    let title = match category {
+        Some(category) => i18n::translate(category.title(), &[]),
+        None => t!("Other"),
+    };
+    color::paint("35;1", format!("== {title} =="))
+}
+
+/// Ctrl-C is caught once the first fix is applied, so that it only cancels
+/// the fix being applied. The rest of the time it still quits right away.
+struct Interrupts {
+    fixing: Arc<AtomicBool>,
+    received: mpsc::UnboundedReceiver<()>,
+}
+
+impl Interrupts {
+    fn listen() -> Self {
+        let fixing = Arc::new(AtomicBool::new(false));
+        let (sender, received) = mpsc::unbounded_channel();
+        let listening = Arc::clone(&fixing);
+        tokio::spawn(async move {
+            while signal::ctrl_c().await.is_ok() {
+                if !listening.load(Ordering::SeqCst) {
+                    // Same as the status of a process killed by SIGINT
+                    process::exit(130);
+                }
+                // The receiver is only dropped at the end of the run
+                let _ = sender.send(());
+            }
+        });
+
+        Interrupts { fixing, received }
+    }
+
+    /// Runs the fix until it's done, or returns `None` if it's interrupted
+    /// first. The external commands it runs are killed when it's dropped.
+    async fn run<T>(&mut self, fix: impl Future<Output = T>) -> Option<T> {
+        // Ctrl-C may have been pressed right after the previous fix
+        while self.received.try_recv().is_ok() {}
+
+        self.fixing.store(true, Ordering::SeqCst);
+        let outcome = tokio::select! {
+            outcome = fix => Some(outcome),
+            _ = self.received.recv() => None,
+        };
+        self.fixing.store(false, Ordering::SeqCst);
+        outcome
+    }
+}
+
+/// The fixes applied during a run, with the hooks that run around all of them
+struct Fixes<'a> {
+    runner: &'a Runner,
+    /// The commands whose fix was applied, once the first one is
+    applied: Option<Vec<String>>,
+    /// What they did in total
+    fixed: Option<FixResult>,
+    /// The ones that failed
+    failures: Vec<Failure>,
+    interrupts: Option<Interrupts>,
+    /// The command whose fix was interrupted, with the directories left
+    /// partially removed
+    interrupted: Option<(String, Vec<PathBuf>)>,
+}
+
+impl Fixes<'_> {
+    async fn apply(&mut self, result: &CheckResult, frontend: &mut dyn Renderer) -> Result<()> {
+        // Most runs don't apply anything, so the hooks only run if they do
+        let applied = match &mut self.applied {
+            Some(applied) => applied,
+            None => {
+                self.runner.pre_run().await?;
+                self.applied.insert(Vec::new())
+            }
+        };
+        let interrupts = self.interrupts.get_or_insert_with(Interrupts::listen);
+
+        frontend.render(Event::FixStarted(result))?;
+        let outcome = match interrupts.run(result.apply_fix()).await {
+            Some(outcome) => outcome,
+            None => {
+                let partial = undo::interrupt().await?;
+                self.interrupted = Some((result.key(), partial));
+                return Ok(());
+            }
+        };
+        match &outcome {
+            Ok(fixed) => {
+                applied.push(result.key());
+                self.fixed.get_or_insert_with(FixResult::default).add(fixed);
+                // The items it couldn't clean up while it went on
+                self.failures
+                    .extend(fixed.failures.iter().map(|error| Failure {
+                        id: result.key(),
+                        fix: true,
+                        error: error.clone(),
+                    }));
+            }
+            Err(e) => self.failures.push(Failure::new(result, true, e)),
+        }
+        frontend.render(Event::FixApplied(&outcome))
+    }
+
+    /// What was done until the fixes were interrupted, if they were.
+    fn interruption(&self, results: &[CheckResult]) -> Option<Interruption> {
+        let (id, partial) = self.interrupted.clone()?;
+        let applied = self.applied.clone().unwrap_or_default();
+        let not_applied = results
+            .iter()
+            .filter(|result| result.fix_available() && !applied.contains(&result.key()))
+            .map(CheckResult::key)
+            .collect();
+        Some(Interruption {
+            id,
+            partial,
+            applied,
+            not_applied,
+        })
+    }
+
+    async fn finish(self) -> Result<()> {
+        match &self.applied {
+            Some(applied) => self.runner.post_run(applied).await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Runs the checks, showing them with the frontend and applying the fixes it
+/// wants. They're also added to the report as soon as they finish, if any.
+/// The results are returned in the same order as the checks, along with the
+/// summary.
+pub async fn run(
+    runner: &Runner,
+    checks: Vec<Check>,
+    frontend: &mut dyn Renderer,
+    mut report: Option<&mut Report>,
+) -> Result<(Vec<CheckResult>, Summary)> {
+    frontend.render(Event::Started(&checks))?;
+    let group = runner.config(None).group;
+    let mut fixes = Fixes {
+        runner,
+        applied: None,
+        fixed: None,
+        failures: Vec::new(),
+        interrupts: None,
+        interrupted: None,
+    };
+    let mut results = Vec::new();
+    let mut pending = BTreeMap::new();
+    let mut next = 0;
+
+    // Synchonizing the results from the tasks, while the frontend may show
+    // the ones that are still running.
+    let mut running = runner.spawn(checks);
+    let mut ticks = time::interval(Duration::from_millis(100));
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Skip);
+    'checks: while !frontend.stopped() {
+        let result = tokio::select! {
+            result = running.next() => match result {
+                Some(result) => result,
+                None => break,
+            },
+            _ = ticks.tick() => {
+                frontend.render(Event::Tick)?;
+                continue;
+            }
+        };
+        if let Some(report) = &mut report {
+            report.add(&result);
+        }
+
+        let ready = if group {
+            pending.insert(result.index, result);
+            let mut ready = Vec::new();
+            while let Some(result) = pending.remove(&next) {
+                ready.push(result);
+                next += 1;
+            }
+            ready
+        } else {
+            vec![result]
+        };
+        for result in ready {
+            frontend.render(Event::CheckFinished(&result))?;
+
+            // The fixes are applied sequentially so that the user sees the
+            // results of the command
+            if result.fix_available() && frontend.confirm(&result)? {
+                fixes.apply(&result, frontend).await?;
+            }
+            results.push(result);
+            if fixes.interrupted.is_some() {
+                break 'checks;
+            }
+        }
+    }
+
+    // Wait for any work left in the tasks, which should be none at this point
+    // anyway, unless the frontend stopped waiting or the fixes were
+    // interrupted.
+    if !frontend.stopped() && fixes.interrupted.is_none() {
+        running.join().await?;
+    }
+    if fixes.interrupted.is_none() {
+        for index in frontend.selected()? {
+            if let Some(result) = results.iter().find(|result| result.index == index) {
+                fixes.apply(result, frontend).await?;
+            }
+            if fixes.interrupted.is_some() {
+                break;
+            }
+        }
+    }
+    let interruption = fixes.interruption(&results);
+    let fixed = fixes.fixed.take();
+    let fix_failures = std::mem::take(&mut fixes.failures);
+    fixes.finish().await?;
+    if let Some(interruption) = interruption {
+        frontend.render(Event::Interrupted(&interruption))?;
+        bail!("the fixes were interrupted");
+    }
+
+    results.sort_by_key(|result| result.index);
+    let mut summary = Summary {
+        fixed,
+        ..Summary::new(&results)
+    };
+    summary.failures.extend(fix_failures);
+    frontend.render(Event::Finished(&summary))?;
+    Ok((results, summary))
+}
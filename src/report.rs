@@ -0,0 +1,266 @@
+use arch_clean::{cmd, registry::Category, runner::CheckResult, Severity};
+
+use std::{
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Context, Result};
+
+/// The format of the report, chosen by the extension of its file
+#[derive(Clone, Copy)]
+enum Format {
+    Markdown,
+    Html,
+}
+
+/// A result as shown in the report, taken right when the check finishes.
+struct Entry {
+    id: String,
+    title: String,
+    category: Option<Category>,
+    severity: Option<Severity>,
+    reclaimable_bytes: Option<u64>,
+    content: Option<String>,
+    fix: Option<String>,
+    error: Option<String>,
+    /// Whether the error is that a dependency is missing
+    skipped: bool,
+    /// Seconds since the Unix epoch
+    finished: u64,
+}
+
+impl Entry {
+    fn error_label(&self) -> &'static str {
+        if self.skipped {
+            "Skipped"
+        } else {
+            "Failed to run"
+        }
+    }
+}
+
+/// All the results of a run in a standalone file, e.g., to attach it to a
+/// ticket.
+pub struct Report {
+    path: PathBuf,
+    format: Format,
+    started: u64,
+    entries: Vec<Entry>,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |time| time.as_secs())
+}
+
+/// E.g. `2022-03-14 09:26:53 UTC`, without pulling in a date library.
+fn format_time(secs: u64) -> String {
+    // Days to a civil date, from http://howardhinnant.github.io/date_algorithms.html
+    let days = (secs / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let doe = days.rem_euclid(146097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    let time = secs % 86400;
+    format!(
+        "{year}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    )
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl Report {
+    /// Fails if the extension of the file isn't known, before running
+    /// anything.
+    pub fn new(path: &Path) -> Result<Report> {
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("md" | "markdown") => Format::Markdown,
+            Some("html" | "htm") => Format::Html,
+            _ => bail!("unknown report format, expected a `.md` or `.html` file"),
+        };
+
+        Ok(Report {
+            path: path.to_path_buf(),
+            format,
+            started: now(),
+            entries: Vec::new(),
+        })
+    }
+
+    pub fn add(&mut self, result: &CheckResult) {
+        let (title, content, reclaimable_bytes, fix) = match &result.output {
+            Ok(out) => (
+                out.title.clone(),
+                Some(out.content.clone()),
+                out.reclaimable_bytes,
+                out.fix_available.then(|| result.fix_description()),
+            ),
+            Err(_) => (result.id.clone(), None, None, None),
+        };
+
+        self.entries.push(Entry {
+            id: result.id.clone(),
+            title,
+            category: result.category,
+            severity: result.severity(),
+            reclaimable_bytes,
+            content,
+            fix,
+            error: result.output.as_ref().err().map(ToString::to_string),
+            skipped: result.skipped().is_some(),
+            finished: now(),
+        });
+    }
+
+    /// The total size that can be freed and how many checks have a fix.
+    fn totals(&self) -> (u64, usize) {
+        let fixes = self.entries.iter().filter(|entry| entry.fix.is_some());
+        let bytes = fixes
+            .clone()
+            .filter_map(|entry| entry.reclaimable_bytes)
+            .sum();
+        (bytes, fixes.count())
+    }
+
+    fn markdown(&self) -> String {
+        let (bytes, fixes) = self.totals();
+        let mut md = String::new();
+        writeln!(md, "# arch-clean report\n").unwrap();
+        writeln!(md, "Generated on {}.\n", format_time(self.started)).unwrap();
+        writeln!(
+            md,
+            "{} checks run, {fixes} with fixes available. About {} can be freed by applying all of them.",
+            self.entries.len(),
+            cmd::human_size(bytes)
+        )
+        .unwrap();
+
+        for entry in &self.entries {
+            writeln!(md, "\n## {}\n", entry.title).unwrap();
+            writeln!(md, "- Command: `{}`", entry.id).unwrap();
+            if let Some(category) = entry.category {
+                writeln!(md, "- Category: {category}").unwrap();
+            }
+            if let Some(severity) = entry.severity {
+                writeln!(md, "- Severity: {severity}").unwrap();
+            }
+            if let Some(bytes) = entry.reclaimable_bytes {
+                writeln!(md, "- Reclaimable: {}", cmd::human_size(bytes)).unwrap();
+            }
+            writeln!(md, "- Finished: {}", format_time(entry.finished)).unwrap();
+            if let Some(error) = &entry.error {
+                writeln!(md, "\n{}: {error}", entry.error_label()).unwrap();
+            }
+            if let Some(content) = &entry.content {
+                writeln!(md, "\n```\n{}\n```", content.trim_end()).unwrap();
+            }
+            if let Some(fix) = &entry.fix {
+                writeln!(md, "\n### Fix\n\n```\n{}\n```", fix.trim_end()).unwrap();
+            }
+        }
+
+        md
+    }
+
+    fn html(&self) -> String {
+        let (bytes, fixes) = self.totals();
+        let mut html = String::new();
+        writeln!(
+            html,
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>arch-clean report</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: auto; padding: 1em; }}
+pre {{ background: #f4f4f4; padding: 0.5em; overflow-x: auto; }}
+.warn {{ color: #b58900; }}
+.critical {{ color: #dc322f; }}
+</style>
+</head>
+<body>
+<h1>arch-clean report</h1>
+<p>Generated on {}.</p>
+<p>{} checks run, {fixes} with fixes available. About {} can be freed by applying all of them.</p>",
+            format_time(self.started),
+            self.entries.len(),
+            cmd::human_size(bytes)
+        )
+        .unwrap();
+
+        for entry in &self.entries {
+            let class = match entry.severity {
+                Some(Severity::Warn) => " class=\"warn\"",
+                Some(Severity::Critical) => " class=\"critical\"",
+                _ => "",
+            };
+            writeln!(html, "<h2{class}>{}</h2>\n<ul>", escape_html(&entry.title)).unwrap();
+            writeln!(
+                html,
+                "<li>Command: <code>{}</code></li>",
+                escape_html(&entry.id)
+            )
+            .unwrap();
+            if let Some(category) = entry.category {
+                writeln!(html, "<li>Category: {category}</li>").unwrap();
+            }
+            if let Some(severity) = entry.severity {
+                writeln!(html, "<li>Severity: {severity}</li>").unwrap();
+            }
+            if let Some(bytes) = entry.reclaimable_bytes {
+                writeln!(html, "<li>Reclaimable: {}</li>", cmd::human_size(bytes)).unwrap();
+            }
+            writeln!(
+                html,
+                "<li>Finished: {}</li>\n</ul>",
+                format_time(entry.finished)
+            )
+            .unwrap();
+            if let Some(error) = &entry.error {
+                let label = entry.error_label();
+                writeln!(html, "<p>{label}: {}</p>", escape_html(error)).unwrap();
+            }
+            if let Some(content) = &entry.content {
+                writeln!(html, "<pre>{}</pre>", escape_html(content.trim_end())).unwrap();
+            }
+            if let Some(fix) = &entry.fix {
+                writeln!(
+                    html,
+                    "<h3>Fix</h3>\n<pre>{}</pre>",
+                    escape_html(fix.trim_end())
+                )
+                .unwrap();
+            }
+        }
+
+        writeln!(html, "</body>\n</html>").unwrap();
+        html
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let content = match self.format {
+            Format::Markdown => self.markdown(),
+            Format::Html => self.html(),
+        };
+        fs::write(&self.path, content)
+            .with_context(|| format!("couldn't write the report to {}", self.path.display()))
+    }
+}
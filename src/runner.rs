@@ -0,0 +1,398 @@
+use crate::{
+    cache,
+    cmd::{CleanupCommand, FixResult, Output, Severity},
+    config::{Config, ConfigFile, Options},
+    fleet, hooks, privilege,
+    registry::{self, Category, Check, MissingDependency},
+    undo, users,
+};
+
+use std::{
+    io,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Result};
+use tokio::{
+    sync::{mpsc, Semaphore},
+    task::{self, JoinHandle},
+    time,
+};
+use tracing::{debug, info, info_span, warn, Instrument};
+
+/// Runs the checks with the configuration file and the options that override
+/// it, e.g., from the command line.
+pub struct Runner {
+    file: ConfigFile,
+    overrides: Options,
+}
+
+impl Runner {
+    pub fn new(file: ConfigFile, overrides: Options) -> Self {
+        Runner { file, overrides }
+    }
+
+    /// Uses the user's configuration file without overrides.
+    pub fn load() -> Result<Self> {
+        Ok(Runner::new(ConfigFile::load()?, Options::default()))
+    }
+
+    pub fn config_file(&self) -> &ConfigFile {
+        &self.file
+    }
+
+    /// The configuration of a check, or the global one with `None`.
+    pub fn config(&self, id: Option<&str>) -> Config {
+        self.file.resolve(id, &self.overrides)
+    }
+
+    /// Runs the `pre-run` hook, if any. It's meant to be called before the
+    /// first fix is applied.
+    pub async fn pre_run(&self) -> Result<()> {
+        match self.config(None).pre_run {
+            Some(hook) => hooks::run(&hook, &[]).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the `post-run` hook, if any, with the identifiers of the commands
+    /// whose fixes were applied in `ARCH_CLEAN_IDS`, separated by commas.
+    pub async fn post_run(&self, applied: &[String]) -> Result<()> {
+        match self.config(None).post_run {
+            Some(hook) => hooks::run(&hook, &[("ARCH_CLEAN_IDS", applied.join(","))]).await,
+            None => Ok(()),
+        }
+    }
+
+    /// The checks to run, including the custom ones, see [`registry::select`].
+    /// They're limited to the configured profile and categories, if any, and
+    /// sorted by category with `group`. With `all-users`, the ones that only
+    /// inspect the home directory are run for each user, which needs root.
+    /// With `hosts`, the built-in ones are run on each of them instead, see
+    /// [`fleet`].
+    pub fn select(&self, only: Option<&str>, skip: Option<&str>) -> Result<Vec<Check>> {
+        let config = self.config(None);
+        let mut checks = registry::select(
+            only,
+            skip,
+            config.profile.as_deref(),
+            config.category.as_deref(),
+            &self.file,
+        )?;
+        if !config.hosts.is_empty() {
+            if config.all_users {
+                bail!("all the users can't be checked on the hosts");
+            }
+            checks = fleet::for_hosts(checks, &config.hosts, config.refresh);
+        } else if config.all_users {
+            if !privilege::is_root() {
+                bail!("all the users can only be checked as root");
+            }
+            checks = registry::for_users(checks, &users::all()?);
+        }
+        if config.group {
+            // The ones without a category go last
+            checks.sort_by_key(|check| (check.category.is_none(), check.category));
+        }
+
+        Ok(checks)
+    }
+
+    /// Only the check with the given identifier, even if it isn't in the
+    /// configured profile.
+    pub fn select_one(&self, id: &str) -> Result<Vec<Check>> {
+        if id.contains(',') {
+            bail!("only one command can be given, use --only to run several");
+        }
+        registry::select(Some(id), None, None, None, &self.file)
+    }
+
+    /// The check commands are each run in a separate task, with their own
+    /// configuration. Their results can be received as they finish. Only up
+    /// to `jobs` of them run at the same time, since most are IO-heavy.
+    pub fn spawn(&self, checks: Vec<Check>) -> Running {
+        let jobs = self.file.resolve(None, &self.overrides).jobs;
+        let jobs = Arc::new(Semaphore::new(jobs.max(1)));
+        let (wr, rd) = mpsc::unbounded_channel();
+        let mut handles = Vec::with_capacity(checks.len());
+        for (index, check) in checks.into_iter().enumerate() {
+            let wr = wr.clone();
+            let jobs = Arc::clone(&jobs);
+            let mut config = self.file.resolve(Some(&check.id), &self.overrides);
+            config.home = check.user.as_ref().map(|user| user.home.clone());
+            let user = check.user.as_ref().map(|user| user.name.clone());
+            let host = check.host.clone();
+            let key = result_key(&check.id, user.as_deref(), host.as_deref());
+            let span = info_span!("check", id = %key);
+            let task = async move {
+                // The semaphore is never closed
+                let _permit = jobs.acquire().await.unwrap();
+                debug!(?config, "running check");
+                let start = Instant::now();
+                let missing = check.missing_dependency(&config);
+                let mut cmd = check.cmd;
+                let cache_key = cmd
+                    .cache_key(&config)
+                    .filter(|_| config.cache_ttl > 0 && missing.is_none());
+                let cached = match &cache_key {
+                    Some(cache_key) if !config.refresh => {
+                        cache::load(&key, cache_key, config.cache_ttl, cmd.as_mut())
+                    }
+                    _ => None,
+                };
+                let is_cached = cached.is_some();
+                let output = match (cached, missing, config.timeout) {
+                    (Some(out), _, _) => Ok(out),
+                    (None, Some(missing), _) => Err(missing.into()),
+                    (None, None, None) => cmd.check(&config).await,
+                    // The check is cancelled by dropping it, and the rest
+                    // keep running.
+                    (None, None, Some(secs)) => {
+                        time::timeout(Duration::from_secs(secs), cmd.check(&config))
+                            .await
+                            .unwrap_or_else(|_| Err(anyhow!("timed out after {secs}s")))
+                    }
+                };
+                let elapsed = start.elapsed();
+                match &output {
+                    Ok(out) => info!(
+                        ?elapsed,
+                        fix_available = out.fix_available,
+                        "check finished"
+                    ),
+                    Err(e) => warn!(?elapsed, error = %e, "check failed"),
+                }
+                if let (Some(cache_key), Ok(out), false) = (&cache_key, &output, is_cached) {
+                    if let Err(e) = cache::save(&key, cache_key, out, cmd.as_ref()) {
+                        warn!(error = %e, "couldn't cache the result");
+                    }
+                }
+                // After caching it, since it doesn't depend on the threshold
+                let output = output.map(|mut out| {
+                    out.filter_min_size(config.min_size);
+                    if let Some(user) = &user {
+                        out.title = format!("[{user}] {}", out.title);
+                    }
+                    out
+                });
+
+                // The receiver may be gone if the results aren't wanted anymore
+                let _ = wr.send(CheckResult {
+                    index,
+                    id: check.id,
+                    description: check.description,
+                    category: check.category,
+                    user,
+                    host,
+                    cmd,
+                    config,
+                    output,
+                });
+            };
+            handles.push(task::spawn(task.instrument(span)));
+        }
+
+        Running { rd, handles }
+    }
+}
+
+/// The checks that are currently running.
+pub struct Running {
+    rd: mpsc::UnboundedReceiver<CheckResult>,
+    handles: Vec<JoinHandle<()>>,
+}
+
+impl Running {
+    /// The next check to finish, or `None` when all of them are done.
+    pub async fn next(&mut self) -> Option<CheckResult> {
+        self.rd.recv().await
+    }
+
+    /// Same as [`Running::next`], without waiting for it.
+    pub fn try_next(&mut self) -> Option<CheckResult> {
+        self.rd.try_recv().ok()
+    }
+
+    /// Waits for all the checks and returns them in the order they were
+    /// selected.
+    pub async fn collect(mut self) -> Result<Vec<CheckResult>> {
+        let mut results = Vec::with_capacity(self.handles.len());
+        while let Some(result) = self.next().await {
+            results.push(result);
+        }
+        self.join().await?;
+
+        results.sort_by_key(|result| result.index);
+        Ok(results)
+    }
+
+    /// Wait for any work left in the tasks, which fails if any of them
+    /// panicked.
+    pub async fn join(self) -> Result<()> {
+        for handle in self.handles {
+            handle.await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A finished check, with which its fix can be applied.
+pub struct CheckResult {
+    /// The position of the check in the ones that were selected
+    pub index: usize,
+    pub id: String,
+    pub description: String,
+    pub category: Option<Category>,
+    /// The user whose home directory was checked, if it's not the invoking one
+    pub user: Option<String>,
+    /// The machine it was run on, if it's not this one
+    pub host: Option<String>,
+    pub cmd: Box<dyn CleanupCommand>,
+    pub config: Config,
+    pub output: Result<Output>,
+}
+
+/// The identifier of the command, followed by `@user` for the other users,
+/// and preceded by `host:` for the other machines.
+fn result_key(id: &str, user: Option<&str>, host: Option<&str>) -> String {
+    let mut key = id.to_string();
+    if let Some(user) = user {
+        key = format!("{key}@{user}");
+    }
+    if let Some(host) = host {
+        key = format!("{host}:{key}");
+    }
+    key
+}
+
+impl CheckResult {
+    /// Identifies the result among the ones of a run, since the same command
+    /// may be run for several users or hosts, see [`Runner::select`].
+    pub fn key(&self) -> String {
+        result_key(&self.id, self.user.as_deref(), self.host.as_deref())
+    }
+
+    pub fn fix_available(&self) -> bool {
+        matches!(&self.output, Ok(out) if out.fix_available)
+    }
+
+    /// Why the check was skipped, if it was.
+    pub fn skipped(&self) -> Option<&MissingDependency> {
+        self.output.as_ref().err()?.downcast_ref()
+    }
+
+    /// See [`Output::severity`], or `None` if the check failed.
+    pub fn severity(&self) -> Option<Severity> {
+        self.output
+            .as_ref()
+            .ok()
+            .map(|out| out.severity(&self.config))
+    }
+
+    /// Whether the result should be shown according to the configured
+    /// minimum severity, `fixes-only` and `hide-empty`. Failed checks are
+    /// always shown, unlike the skipped ones with `fixes-only`.
+    pub fn is_shown(&self) -> bool {
+        if self.config.fixes_only && !self.fix_available() {
+            return self.output.is_err() && self.skipped().is_none();
+        }
+        if self.config.hide_empty && self.output.as_ref().is_ok_and(Output::is_empty) {
+            return false;
+        }
+
+        self.severity()
+            .is_none_or(|severity| severity >= self.config.min_severity)
+    }
+
+    /// See [`CleanupCommand::show_fix`], printed to stdout.
+    pub fn show_fix(&self) -> io::Result<()> {
+        self.cmd.show_fix(&self.config, &mut io::stdout())
+    }
+
+    /// What [`CleanupCommand::show_fix`] shows, to include it somewhere else.
+    pub fn fix_description(&self) -> String {
+        let mut out = Vec::new();
+        // Writing to a `Vec` can't fail
+        self.cmd.show_fix(&self.config, &mut out).unwrap();
+        String::from_utf8_lossy(&out).into_owned()
+    }
+
+    /// The environment of the hooks, with the identifier of the command in
+    /// `ARCH_CLEAN_ID` and the size its fix would free in
+    /// `ARCH_CLEAN_RECLAIMABLE_BYTES`, if known.
+    fn hook_env(&self) -> Vec<(&'static str, String)> {
+        let mut env = vec![("ARCH_CLEAN_ID", self.id.clone())];
+        if let Some(bytes) = self
+            .output
+            .as_ref()
+            .ok()
+            .and_then(|out| out.reclaimable_bytes)
+        {
+            env.push(("ARCH_CLEAN_RECLAIMABLE_BYTES", bytes.to_string()));
+        }
+        env
+    }
+
+    /// See [`CleanupCommand::apply_fix`]. What it does is recorded so that it
+    /// can be reverted with [`undo::undo`], even if it fails halfway.
+    ///
+    /// The `pre-fix` hook runs first, and the fix isn't applied if it fails.
+    /// The `post-fix` hook runs afterwards even if the fix failed, with
+    /// `ARCH_CLEAN_RESULT` set to `applied` or `failed`, and the error in
+    /// `ARCH_CLEAN_ERROR`. The space it freed is in `ARCH_CLEAN_FREED_BYTES`,
+    /// if known.
+    pub async fn apply_fix(&self) -> Result<FixResult> {
+        let span = info_span!("fix", id = %self.key());
+        async {
+            if let Some(hook) = &self.config.pre_fix {
+                hooks::run(hook, &self.hook_env()).await?;
+            }
+
+            info!("applying fix");
+            // Whatever is cached will be outdated, even if the fix is
+            // interrupted
+            if let Err(e) = cache::remove(&self.key()) {
+                warn!(error = %e, "couldn't remove the cached result");
+            }
+            undo::begin(&self.id, self.config.permanent).await?;
+            let result = self.cmd.apply_fix(&self.config).await;
+            undo::finish().await?;
+            match &result {
+                Ok(fixed) => info!(
+                    freed_bytes = fixed.freed_bytes,
+                    removed = fixed.removed,
+                    "fix applied"
+                ),
+                Err(e) => warn!(error = %e, "fix failed"),
+            }
+
+            let hook_result = match &self.config.post_fix {
+                Some(hook) => {
+                    let mut env = self.hook_env();
+                    match &result {
+                        Ok(fixed) => {
+                            env.push(("ARCH_CLEAN_RESULT", "applied".to_string()));
+                            if let Some(bytes) = fixed.freed_bytes {
+                                env.push(("ARCH_CLEAN_FREED_BYTES", bytes.to_string()));
+                            }
+                        }
+                        Err(e) => {
+                            env.push(("ARCH_CLEAN_RESULT", "failed".to_string()));
+                            env.push(("ARCH_CLEAN_ERROR", e.to_string()));
+                        }
+                    }
+                    hooks::run(hook, &env).await
+                }
+                None => Ok(()),
+            };
+            // The error of the fix is more important
+            let fixed = result?;
+            hook_result?;
+            Ok(fixed)
+        }
+        .instrument(span)
+        .await
+    }
+}
@@ -0,0 +1,188 @@
+use crate::{
+    progress::Progress,
+    render::{self, Event, Renderer},
+};
+
+use arch_clean::{cmd, color, config::ConfirmPolicy, registry::Category, runner::CheckResult, t};
+
+use std::{collections::HashMap, iter};
+
+use anyhow::Result;
+
+/// The results printed as they finish, with the checks that are still running
+/// below them. With placeholders, they're printed in the same order as the
+/// checks instead, see [`Progress`].
+pub struct Text {
+    /// Whether only a one-line summary is printed at the end
+    quiet: bool,
+    /// The only commands whose fix is applied, if not all
+    apply_only: Option<Vec<String>>,
+    group: bool,
+    progress: Option<Progress>,
+    /// The progress is disabled, e.g., if something else is written to stderr
+    progress_enabled: bool,
+    /// Whether the progress lists all the checks from the start, which can't
+    /// be done when the fixes are confirmed along with them
+    placeholders: bool,
+    /// The results that finished before the ones above them, by the position
+    /// of their check, with placeholders
+    waiting: HashMap<usize, Option<Shown>>,
+    /// The category of the last result shown, when they're grouped
+    category: Option<Option<Category>>,
+}
+
+/// What is printed for a result
+struct Shown {
+    category: Option<Category>,
+    text: String,
+    /// Whether it's why the check was skipped, which is written to stderr
+    skipped: bool,
+}
+
+impl Shown {
+    /// Nothing for the results that are hidden, or that failed, whose errors
+    /// are shown at the end, see `Summary::failures`.
+    fn new(result: &CheckResult) -> Option<Shown> {
+        if !result.is_shown() {
+            return None;
+        }
+
+        let (text, skipped) = match (&result.output, result.skipped()) {
+            (_, Some(missing)) => {
+                let skipped = t!(
+                    "Skipped {id}: {reason}",
+                    id = result.key(),
+                    reason = missing
+                );
+                (skipped, true)
+            }
+            (Err(_), None) => return None,
+            (Ok(out), None) => (out.render(out.severity(&result.config)), false),
+        };
+        Some(Shown {
+            category: result.category,
+            text,
+            skipped,
+        })
+    }
+}
+
+impl Text {
+    pub fn new(
+        quiet: bool,
+        apply_only: Option<&str>,
+        group: bool,
+        progress_enabled: bool,
+        placeholders: bool,
+    ) -> Self {
+        Text {
+            quiet,
+            apply_only: apply_only
+                .map(|ids| ids.split(',').map(|id| id.trim().to_owned()).collect()),
+            group,
+            progress: None,
+            progress_enabled,
+            placeholders,
+            waiting: HashMap::new(),
+            category: None,
+        }
+    }
+
+    fn show(&mut self, shown: Shown) {
+        if self.group && self.category != Some(shown.category) {
+            println!("{}\n", render::category_header(shown.category));
+            self.category = Some(shown.category);
+        }
+
+        if shown.skipped {
+            eprintln!("{}", shown.text);
+        } else {
+            println!("{}", shown.text);
+        }
+    }
+}
+
+impl Renderer for Text {
+    fn render(&mut self, event: Event<'_>) -> Result<()> {
+        match event {
+            Event::Started(checks) => {
+                self.progress = Some(Progress::new(
+                    checks,
+                    self.progress_enabled,
+                    self.placeholders,
+                ));
+            }
+            Event::Tick => {
+                if let Some(progress) = &mut self.progress {
+                    progress.draw()?;
+                }
+            }
+            Event::CheckFinished(result) => {
+                let shown = Shown::new(result);
+                let Some(progress) = &mut self.progress else {
+                    if let Some(shown) = shown {
+                        self.show(shown);
+                    }
+                    return Ok(());
+                };
+
+                progress.clear()?;
+                let preview = shown.as_ref().map(|shown| shown.text.clone());
+                progress.done(result.index, preview);
+                self.waiting.insert(result.index, shown);
+                let ready = iter::from_fn(|| progress.next()).collect::<Vec<_>>();
+                for index in ready {
+                    if let Some(shown) = self.waiting.remove(&index).flatten() {
+                        self.show(shown);
+                    }
+                }
+            }
+            Event::FixStarted(_) => {}
+            Event::FixApplied(outcome) => render::print_outcome(outcome),
+            Event::Interrupted(interruption) => println!("{interruption}"),
+            Event::Finished(summary) => {
+                if self.quiet {
+                    println!("{}", summary.short());
+                } else {
+                    println!("{summary}");
+                }
+                if let Some(failures) = summary.failures() {
+                    eprint!("{failures}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The fix is a two-step process, first we make sure that the user wants
+    /// to continue. This is a blocking operation. What the fix does is shown
+    /// anyway when it's applied without confirmation, so that there's a
+    /// record of it.
+    fn confirm(&mut self, result: &CheckResult) -> Result<bool> {
+        // They will only be applied when configured
+        let applies = self
+            .apply_only
+            .as_ref()
+            .is_none_or(|ids| ids.contains(&result.id));
+        if !result.is_shown()
+            || !result.config.apply
+            || !applies
+            || result.config.confirm == Some(ConfirmPolicy::Never)
+        {
+            return Ok(false);
+        }
+
+        result.show_fix()?;
+        let confirmed = match result.config.confirm {
+            Some(ConfirmPolicy::Auto) => true,
+            None if result.config.yes => true,
+            _ => cmd::confirm(&t!("Confirm?"))?,
+        };
+        if !confirmed {
+            println!("{}\n", color::paint("31", t!("Skipped")));
+        }
+
+        Ok(confirmed)
+    }
+}
@@ -0,0 +1,88 @@
+//! A user-level systemd timer can run the checks periodically, without
+//! applying any fix. The report of the last run is saved in
+//! `~/.local/state/arch-clean/report.json`, and the run is also added to the
+//! history, so that `--diff` shows what changed since then.
+
+use crate::process::TracedCommand;
+
+use std::{env, fs, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use tokio::process::Command;
+
+/// The name of both units
+const NAME: &str = "arch-clean";
+
+fn dir() -> PathBuf {
+    let config_dir = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap()).join(".config"));
+    config_dir.join("systemd/user")
+}
+
+/// The checks are run in report-only mode, since there's nobody to confirm
+/// the fixes. `%S` is the user's state directory, which systemd creates
+/// because of `StateDirectory`.
+fn service(notify: bool) -> Result<String> {
+    let exe = env::current_exe().context("couldn't find the arch-clean executable")?;
+    let notify = if notify { " --notify" } else { "" };
+    Ok(format!(
+        "[Unit]
+Description=Check what can be cleaned up in the system
+
+[Service]
+Type=oneshot
+StateDirectory={NAME}
+ExecStart={} --format json{notify}
+StandardOutput=truncate:%S/{NAME}/report.json
+",
+        exe.display()
+    ))
+}
+
+/// `Persistent` makes up for the runs missed while the computer was off.
+fn timer(on_calendar: &str) -> String {
+    format!(
+        "[Unit]
+Description=Check what can be cleaned up in the system periodically
+
+[Timer]
+OnCalendar={on_calendar}
+Persistent=true
+
+[Install]
+WantedBy=timers.target
+"
+    )
+}
+
+async fn systemctl(args: &[&str]) -> Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .traced_status()
+        .await
+        .context("couldn't run systemctl")?;
+    if !status.success() {
+        bail!("`systemctl --user {}` failed with {status}", args.join(" "));
+    }
+
+    Ok(())
+}
+
+/// Writes the units and enables the timer, which runs the checks when
+/// `on_calendar` says, e.g. `weekly` or `Mon *-*-* 10:00`. With `notify`, the
+/// summary is also sent as a desktop notification. Returns the directory of
+/// the units.
+pub async fn install(on_calendar: &str, notify: bool) -> Result<PathBuf> {
+    let dir = dir();
+    fs::create_dir_all(&dir).with_context(|| format!("couldn't create {}", dir.display()))?;
+    for (ext, content) in [("service", service(notify)?), ("timer", timer(on_calendar))] {
+        let path = dir.join(format!("{NAME}.{ext}"));
+        fs::write(&path, content).with_context(|| format!("couldn't write {}", path.display()))?;
+    }
+
+    systemctl(&["daemon-reload"]).await?;
+    systemctl(&["enable", "--now", &format!("{NAME}.timer")]).await?;
+    Ok(dir)
+}
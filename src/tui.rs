@@ -0,0 +1,352 @@
+use crate::render::{self, Event, Renderer};
+
+use arch_clean::{color, config::ConfirmPolicy, runner::CheckResult, t, Severity};
+
+use std::time::Duration;
+
+use anyhow::Result;
+use ratatui::{
+    crossterm::event::{self, Event as TermEvent, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style, Stylize},
+    text::{Line, Text},
+    widgets::{Block, List, ListItem, ListState, Paragraph},
+    DefaultTerminal, Frame,
+};
+use tokio::task;
+
+const SPINNER: [char; 4] = ['|', '/', '-', '\\'];
+
+/// What is shown of a finished check
+enum Shown {
+    Ok {
+        title: String,
+        content: String,
+        fix_available: bool,
+        severity: Option<Severity>,
+    },
+    Err {
+        message: String,
+        /// Whether the error is that a dependency is missing
+        skipped: bool,
+    },
+}
+
+/// A command in the list, whose check may still be running.
+struct Item {
+    /// The position of the check in the ones that were selected
+    index: usize,
+    description: String,
+    result: Option<Shown>,
+    /// Fixes that are never applied according to the configuration can't
+    /// be selected either.
+    fix_available: bool,
+    expanded: bool,
+    selected: bool,
+}
+
+impl Item {
+    fn render(&self, tick: usize) -> ListItem<'static> {
+        let mark = match (self.fix_available, self.selected) {
+            (true, true) => "[x] ",
+            (true, false) => "[ ] ",
+            (false, _) => "    ",
+        };
+        let arrow = if self.expanded { "v " } else { "> " };
+
+        let mut text = Text::default();
+        match &self.result {
+            None => {
+                let spinner = SPINNER[tick % SPINNER.len()];
+                text.push_line(Line::from(vec![
+                    mark.into(),
+                    arrow.into(),
+                    self.description.clone().dark_gray(),
+                    format!(" {spinner}").dark_gray(),
+                ]));
+            }
+            Some(Shown::Err { message, skipped }) => {
+                // Missing dependencies aren't really an error
+                let (description, msg) = if *skipped {
+                    (self.description.clone().dark_gray(), t!("Skipped"))
+                } else {
+                    (self.description.clone().red(), t!("Failed to run command"))
+                };
+                text.push_line(Line::from(vec![mark.into(), arrow.into(), description]));
+                if self.expanded {
+                    text.push_line(Line::from(format!("      {msg}: {message}")));
+                }
+            }
+            Some(Shown::Ok {
+                title,
+                content,
+                fix_available,
+                severity,
+            }) => {
+                let color = match severity {
+                    Some(Severity::Critical) => Color::Red,
+                    Some(Severity::Warn) => Color::Yellow,
+                    _ => Color::Cyan,
+                };
+                let fix = if *fix_available {
+                    t!(" (fix available)")
+                } else {
+                    String::new()
+                };
+                text.push_line(Line::from(vec![
+                    mark.into(),
+                    arrow.into(),
+                    format!("{title}{fix}").fg(color).bold(),
+                ]));
+                if self.expanded {
+                    for line in content.trim().lines() {
+                        text.push_line(Line::from(format!("      {line}")));
+                    }
+                }
+            }
+        }
+
+        ListItem::new(text)
+    }
+}
+
+struct App {
+    items: Vec<Item>,
+    state: ListState,
+    tick: usize,
+    /// Finished checks that were removed from the list
+    hidden: usize,
+}
+
+impl App {
+    fn draw(&mut self, frame: &mut Frame) {
+        let [list_area, help_area] =
+            Layout::vertical([Constraint::Min(0), Constraint::Length(1)]).areas(frame.area());
+
+        let done = self
+            .items
+            .iter()
+            .filter(|item| item.result.is_some())
+            .count()
+            + self.hidden;
+        let total = self.items.len() + self.hidden;
+        let title = t!(
+            " arch-clean ({done}/{total} checks done) ",
+            done = done,
+            total = total
+        );
+        let items = self
+            .items
+            .iter()
+            .map(|item| item.render(self.tick))
+            .collect::<Vec<_>>();
+        let list = List::new(items)
+            .block(Block::bordered().title(title))
+            .highlight_style(
+                Style::default()
+                    .bg(Color::DarkGray)
+                    .add_modifier(Modifier::BOLD),
+            );
+        frame.render_stateful_widget(list, list_area, &mut self.state);
+
+        let help = t!("up/down: move  enter: expand  space: toggle fix  a: apply  q: quit");
+        frame.render_widget(Paragraph::new(help).dark_gray(), help_area);
+    }
+
+    fn current(&mut self) -> Option<&mut Item> {
+        self.state.selected().and_then(|i| self.items.get_mut(i))
+    }
+}
+
+/// What the user decided once the TUI is closed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Quit,
+    Apply,
+}
+
+/// The results shown in a full-screen interface as the checks run, where the
+/// fixes to apply can be selected. They are applied once the interface is
+/// closed, so that their output can be seen as usual.
+pub struct Tui {
+    app: App,
+    /// Only while the interface is open
+    terminal: Option<DefaultTerminal>,
+    action: Option<Action>,
+}
+
+impl Tui {
+    pub fn new() -> Self {
+        Tui {
+            app: App {
+                items: Vec::new(),
+                state: ListState::default().with_selected(Some(0)),
+                tick: 0,
+                hidden: 0,
+            },
+            terminal: None,
+            action: None,
+        }
+    }
+
+    fn draw(&mut self) -> Result<()> {
+        if let Some(terminal) = &mut self.terminal {
+            terminal.draw(|frame| self.app.draw(frame))?;
+        }
+        Ok(())
+    }
+
+    /// Restores the terminal, so that the fixes are applied in its main
+    /// screen.
+    fn close(&mut self) {
+        if self.terminal.take().is_some() {
+            ratatui::restore();
+        }
+    }
+
+    /// Handles the keys pressed, waiting up to `timeout` for the first one.
+    fn poll(&mut self, mut timeout: Duration) -> Result<()> {
+        while self.action.is_none() && task::block_in_place(|| event::poll(timeout))? {
+            timeout = Duration::ZERO;
+            let key = match task::block_in_place(event::read)? {
+                TermEvent::Key(key) if key.kind == KeyEventKind::Press => key,
+                _ => continue,
+            };
+            match key.code {
+                KeyCode::Char('q') | KeyCode::Esc => self.action = Some(Action::Quit),
+                KeyCode::Char('a') => self.action = Some(Action::Apply),
+                KeyCode::Up | KeyCode::Char('k') => self.app.state.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => self.app.state.select_next(),
+                KeyCode::Enter => {
+                    if let Some(item) = self.app.current() {
+                        item.expanded = !item.expanded;
+                    }
+                }
+                KeyCode::Char(' ') => {
+                    if let Some(item) = self.app.current() {
+                        if item.fix_available {
+                            item.selected = !item.selected;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if self.action.is_some() {
+            self.close();
+        }
+        Ok(())
+    }
+}
+
+impl Drop for Tui {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+impl Shown {
+    fn new(result: &CheckResult) -> Shown {
+        match &result.output {
+            Ok(out) => Shown::Ok {
+                title: out.title.clone(),
+                content: out.content.clone(),
+                fix_available: out.fix_available,
+                severity: result.severity(),
+            },
+            Err(e) => Shown::Err {
+                message: e.to_string(),
+                skipped: result.skipped().is_some(),
+            },
+        }
+    }
+}
+
+impl Renderer for Tui {
+    fn render(&mut self, event: Event<'_>) -> Result<()> {
+        match event {
+            Event::Started(checks) => {
+                self.app.items = checks
+                    .iter()
+                    .enumerate()
+                    .map(|(index, check)| Item {
+                        index,
+                        description: check.description.clone(),
+                        result: None,
+                        fix_available: false,
+                        expanded: false,
+                        selected: false,
+                    })
+                    .collect();
+                self.terminal = Some(ratatui::init());
+                self.draw()?;
+            }
+            Event::Tick => {
+                self.draw()?;
+                self.app.tick += 1;
+                self.poll(Duration::ZERO)?;
+            }
+            Event::CheckFinished(result) => {
+                let i = self
+                    .app
+                    .items
+                    .iter()
+                    .position(|item| item.index == result.index)
+                    .unwrap();
+                // Results that aren't severe enough are hidden once known
+                if result.is_shown() {
+                    let item = &mut self.app.items[i];
+                    item.fix_available = result.fix_available()
+                        && result.config.confirm != Some(ConfirmPolicy::Never);
+                    item.result = Some(Shown::new(result));
+                } else {
+                    self.app.items.remove(i);
+                    self.app.hidden += 1;
+                }
+                self.draw()?;
+            }
+            Event::FixStarted(result) => {
+                println!(
+                    "{}",
+                    color::paint("36;1", format!("{}:", result.description))
+                );
+                result.show_fix()?;
+            }
+            Event::FixApplied(outcome) => render::print_outcome(outcome),
+            Event::Interrupted(interruption) => println!("{interruption}"),
+            Event::Finished(summary) => {
+                if let Some(line) = summary.fixed_line() {
+                    println!("{line}");
+                }
+                // The interface is closed by now, so they're printed after it
+                if let Some(failures) = summary.failures() {
+                    eprint!("{failures}");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn stopped(&self) -> bool {
+        self.action.is_some()
+    }
+
+    /// The interface is kept open once all the checks are done, until the user
+    /// decides what to do.
+    fn selected(&mut self) -> Result<Vec<usize>> {
+        while self.action.is_none() {
+            self.draw()?;
+            self.app.tick += 1;
+            // Blocking for a bit, which also sets the spinner's pace
+            self.poll(Duration::from_millis(100))?;
+        }
+
+        if self.action == Some(Action::Quit) {
+            return Ok(Vec::new());
+        }
+        let selected = self.app.items.iter().filter(|item| item.selected);
+        Ok(selected.map(|item| item.index).collect())
+    }
+}
@@ -0,0 +1,338 @@
+//! The changes made by the last fix are recorded in
+//! `~/.local/state/arch-clean/undo`, so that they can be reverted with
+//! `arch-clean undo`. The files it removes are moved to the trash instead,
+//! unless `permanent` is set. If that's not possible, they're moved to a
+//! staging area in there, which is emptied when the next fix is applied. Files
+//! that can't be moved either, e.g., because they're in another filesystem,
+//! are removed for good.
+//!
+//! A fix can be interrupted with [`interrupt`], which stops the directories
+//! being removed halfway and still saves what was done until then.
+
+use crate::{config::Config, pkg};
+
+use std::{
+    env, io,
+    os::unix::fs::MetadataExt,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use tokio::{fs, task, time};
+use tracing::warn;
+
+/// The fix being applied, to which the changes are added.
+static CURRENT: Mutex<Option<Current>> = Mutex::new(None);
+/// Set while the fix is being interrupted, so that the directories being
+/// removed are left as they are
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+/// The directories being removed in a blocking task, which keeps running even
+/// if the fix is dropped
+static REMOVING: AtomicUsize = AtomicUsize::new(0);
+
+struct Current {
+    record: Record,
+    staging: PathBuf,
+    permanent: bool,
+    /// The directories that were left partially removed
+    partial: Vec<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum Change {
+    /// A file or directory moved to the trash, with its original path
+    Trashed { path: PathBuf },
+    /// A file or directory moved to the staging area
+    Moved { from: PathBuf, to: PathBuf },
+    /// Packages that were uninstalled as orphans, so they were dependencies
+    RemovedPackages { pkgs: Vec<String> },
+}
+
+/// What a fix did.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Record {
+    /// The identifier of the command
+    pub id: String,
+    pub changes: Vec<Change>,
+}
+
+fn dir() -> PathBuf {
+    let state_dir = env::var_os("XDG_STATE_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(env::var("HOME").unwrap()).join(".local/state"));
+    state_dir.join("arch-clean/undo")
+}
+
+fn record_path() -> PathBuf {
+    dir().join("last.json")
+}
+
+/// Starts recording the changes of a fix, which replace the ones of the
+/// previous fix. With `permanent`, the files it removes can't be restored.
+pub async fn begin(id: &str, permanent: bool) -> Result<()> {
+    let dir = dir();
+    match fs::remove_dir_all(&dir).await {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => {
+            return Err(e).with_context(|| format!("couldn't empty {}", dir.display()));
+        }
+        _ => {}
+    }
+    let staging = dir.join("staging");
+    fs::create_dir_all(&staging)
+        .await
+        .with_context(|| format!("couldn't create {}", staging.display()))?;
+
+    let record = Record {
+        id: id.to_string(),
+        changes: Vec::new(),
+    };
+    *CURRENT.lock().unwrap() = Some(Current {
+        record,
+        staging,
+        permanent,
+        partial: Vec::new(),
+    });
+    Ok(())
+}
+
+/// Saves the changes of the fix that was being applied.
+pub async fn finish() -> Result<()> {
+    let record = match CURRENT.lock().unwrap().take() {
+        Some(current) => current.record,
+        None => return Ok(()),
+    };
+    if record.changes.is_empty() {
+        return Ok(());
+    }
+
+    let path = record_path();
+    fs::write(&path, serde_json::to_string_pretty(&record)?)
+        .await
+        .with_context(|| format!("couldn't write {}", path.display()))
+}
+
+/// Stops the fix being applied, which must have been dropped already, as soon
+/// as the directories it's removing allow it. The changes made so far are
+/// saved, so that they can still be reverted. Returns the directories that
+/// were left partially removed, which can't be restored.
+pub async fn interrupt() -> Result<Vec<PathBuf>> {
+    CANCELLED.store(true, Ordering::SeqCst);
+    while REMOVING.load(Ordering::SeqCst) > 0 {
+        time::sleep(Duration::from_millis(10)).await;
+    }
+    CANCELLED.store(false, Ordering::SeqCst);
+
+    let partial = CURRENT
+        .lock()
+        .unwrap()
+        .as_mut()
+        .map(|current| std::mem::take(&mut current.partial))
+        .unwrap_or_default();
+    finish().await?;
+    Ok(partial)
+}
+
+/// Adds a change to the fix being applied, if any.
+pub(crate) fn record(change: Change) {
+    if let Some(current) = CURRENT.lock().unwrap().as_mut() {
+        current.record.changes.push(change);
+    }
+}
+
+/// Removes a file or a directory with all its contents, keeping it in the
+/// trash or the staging area if a fix is being applied. Returns the disk space
+/// it took.
+pub(crate) async fn remove(path: &Path) -> io::Result<u64> {
+    // Also makes sure that it exists, which trashing it doesn't report the
+    // same way
+    let is_dir = fs::symlink_metadata(path).await?.is_dir();
+    let size = {
+        let path = path.to_path_buf();
+        task::spawn_blocking(move || disk_usage(&path)).await?
+    };
+
+    // Numbered so that files with the same name don't clash
+    let staged = CURRENT
+        .lock()
+        .unwrap()
+        .as_ref()
+        .filter(|current| !current.permanent)
+        .map(|current| {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let index = current.record.changes.len();
+            current.staging.join(format!("{index}-{name}"))
+        });
+    if let Some(staged) = staged {
+        match move_to_trash(path).await {
+            Ok(path) => {
+                record(Change::Trashed { path });
+                return Ok(size);
+            }
+            Err(e) => warn!(path = %path.display(), error = %e, "couldn't move to the trash"),
+        }
+        if fs::rename(path, &staged).await.is_ok() {
+            record(Change::Moved {
+                from: path.to_path_buf(),
+                to: staged,
+            });
+            return Ok(size);
+        }
+    }
+
+    if is_dir {
+        remove_dir(path).await?;
+    } else {
+        fs::remove_file(path).await?;
+    }
+    Ok(size)
+}
+
+/// The disk space taken by a file or a directory with all its contents, like
+/// `du`. What can't be read is left out.
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(meta) = std::fs::symlink_metadata(path) else {
+        return 0;
+    };
+    let mut size = meta.blocks() * 512;
+    if meta.is_dir() {
+        if let Ok(entries) = std::fs::read_dir(path) {
+            size += entries
+                .filter_map(|entry| entry.ok())
+                .map(|entry| disk_usage(&entry.path()))
+                .sum::<u64>();
+        }
+    }
+    size
+}
+
+/// Removes a directory for good in a blocking task, which stops between its
+/// entries once the fix is interrupted. The directory is recorded as partially
+/// removed in that case.
+async fn remove_dir(path: &Path) -> io::Result<()> {
+    let path = path.to_path_buf();
+    REMOVING.fetch_add(1, Ordering::SeqCst);
+    task::spawn_blocking(move || {
+        let result = remove_dir_all(&path);
+        if result.is_err() && CANCELLED.load(Ordering::SeqCst) {
+            if let Some(current) = CURRENT.lock().unwrap().as_mut() {
+                current.partial.push(path);
+            }
+        }
+        REMOVING.fetch_sub(1, Ordering::SeqCst);
+        result
+    })
+    .await?
+}
+
+/// Same as [`std::fs::remove_dir_all`], but checking if the fix was
+/// interrupted before each entry.
+fn remove_dir_all(dir: &Path) -> io::Result<()> {
+    for entry in std::fs::read_dir(dir)? {
+        if CANCELLED.load(Ordering::SeqCst) {
+            return Err(io::Error::new(io::ErrorKind::Interrupted, "interrupted"));
+        }
+        let entry = entry?;
+        // Symlinks aren't followed, they're removed like files
+        if entry.file_type()?.is_dir() {
+            remove_dir_all(&entry.path())?;
+        } else {
+            std::fs::remove_file(entry.path())?;
+        }
+    }
+
+    std::fs::remove_dir(dir)
+}
+
+/// Moves the path to the trash, returning its original path as saved in the
+/// trash, i.e., with its parent canonicalized.
+async fn move_to_trash(path: &Path) -> Result<PathBuf> {
+    let path = path.to_path_buf();
+    task::spawn_blocking(move || {
+        let parent = path.parent().unwrap_or(&path).canonicalize()?;
+        let original = match path.file_name() {
+            Some(name) => parent.join(name),
+            None => parent,
+        };
+        trash::delete(&path)?;
+        Ok(original)
+    })
+    .await?
+}
+
+/// Restores the last item moved to the trash from the path.
+async fn restore_from_trash(path: &Path) -> Result<()> {
+    let path = path.to_path_buf();
+    task::spawn_blocking(move || {
+        let item = trash::os_limited::list()?
+            .into_iter()
+            .filter(|item| item.original_path() == path)
+            .max_by_key(|item| item.time_deleted)
+            .with_context(|| format!("{} isn't in the trash anymore", path.display()))?;
+        trash::os_limited::restore_all([item])?;
+        Ok(())
+    })
+    .await?
+}
+
+/// The changes of the last fix, if they haven't been reverted yet.
+pub async fn last() -> Result<Option<Record>> {
+    let path = record_path();
+    let content = match fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).with_context(|| format!("couldn't read {}", path.display())),
+    };
+
+    let record = serde_json::from_str(&content)
+        .with_context(|| format!("invalid undo record at {}", path.display()))?;
+    Ok(Some(record))
+}
+
+/// Reverts the changes of the last fix, in the opposite order they were
+/// made. The ones that fail are reported and skipped.
+pub async fn undo(record: &Record, config: &Config) -> Result<()> {
+    for change in record.changes.iter().rev() {
+        if let Err(e) = revert(change, config).await {
+            eprintln!("Failed to undo: {e}");
+        }
+    }
+
+    fs::remove_dir_all(dir()).await?;
+    Ok(())
+}
+
+async fn revert(change: &Change, config: &Config) -> Result<()> {
+    match change {
+        Change::Trashed { path } => {
+            restore_from_trash(path)
+                .await
+                .with_context(|| format!("couldn't restore {}", path.display()))?;
+            println!("Restored {}", path.display());
+        }
+        Change::Moved { from, to } => {
+            if fs::symlink_metadata(from).await.is_ok() {
+                bail!("{} already exists", from.display());
+            }
+            if let Some(parent) = from.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            fs::rename(to, from)
+                .await
+                .with_context(|| format!("couldn't restore {}", from.display()))?;
+            println!("Restored {}", from.display());
+        }
+        Change::RemovedPackages { pkgs } => {
+            pkg::backend(config).install_as_deps(config, pkgs).await?;
+            println!("Reinstalled {}", pkgs.join(" "));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+//! The users of the system, whose home directories can be checked all at once
+//! when running as root with `all-users`.
+
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+
+const PASSWD: &str = "/etc/passwd";
+/// The range of the regular users, as in the default `/etc/login.defs`
+const UID_MIN: u32 = 1000;
+const UID_MAX: u32 = 60000;
+
+#[derive(Clone, Debug)]
+pub struct User {
+    pub name: String,
+    pub home: PathBuf,
+}
+
+/// The regular users with a home directory in `/home`, by their name.
+pub fn all() -> Result<Vec<User>> {
+    let passwd = fs::read_to_string(PASSWD).with_context(|| format!("couldn't read {PASSWD}"))?;
+    let mut users = passwd
+        .lines()
+        .filter_map(|line| {
+            // name:password:uid:gid:gecos:home:shell
+            let fields = line.split(':').collect::<Vec<_>>();
+            let uid = fields.get(2)?.parse::<u32>().ok()?;
+            let home = PathBuf::from(fields.get(5)?);
+            let regular = (UID_MIN..UID_MAX).contains(&uid);
+            (regular && home.starts_with("/home") && home.is_dir()).then(|| User {
+                name: fields[0].to_string(),
+                home,
+            })
+        })
+        .collect::<Vec<_>>();
+    users.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(users)
+}
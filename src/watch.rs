@@ -0,0 +1,116 @@
+use crate::render::{self, Event, Renderer};
+
+use arch_clean::{color, history::RunResult, registry::Category, t};
+
+use std::fmt::Write;
+
+use anyhow::Result;
+
+/// The results of each run replacing the previous ones on screen once they're
+/// all ready, highlighting what changed since then.
+pub struct Watch {
+    interval: u64,
+    quiet: bool,
+    group: bool,
+    /// The results of the previous run, to compare with
+    prev: Vec<RunResult>,
+    current: Vec<RunResult>,
+    /// What is printed for each result of the current run, by the position of
+    /// its check
+    shown: Vec<(usize, String)>,
+    /// The category of the last result shown, when they're grouped
+    category: Option<Option<Category>>,
+}
+
+impl Watch {
+    pub fn new(interval: u64, quiet: bool, group: bool) -> Self {
+        Watch {
+            interval,
+            quiet,
+            group,
+            prev: Vec::new(),
+            current: Vec::new(),
+            shown: Vec::new(),
+            category: None,
+        }
+    }
+}
+
+impl Renderer for Watch {
+    fn render(&mut self, event: Event<'_>) -> Result<()> {
+        match event {
+            Event::Started(_) => {
+                self.shown.clear();
+                self.category = None;
+            }
+            Event::CheckFinished(result) => {
+                let run_result = RunResult::new(result);
+                let changes = run_result.as_ref().and_then(|run_result| {
+                    let before = self.prev.iter().find(|before| before.id == run_result.id)?;
+                    Some(run_result.diff(before))
+                });
+                self.current.extend(run_result);
+                if !result.is_shown() {
+                    return Ok(());
+                }
+
+                // Writing to a `String` can't fail
+                let mut shown = String::new();
+                if self.group && self.category != Some(result.category) {
+                    writeln!(shown, "{}\n", render::category_header(result.category)).unwrap();
+                    self.category = Some(result.category);
+                }
+                match (&result.output, result.skipped()) {
+                    (_, Some(missing)) => {
+                        let skipped = t!(
+                            "Skipped {id}: {reason}",
+                            id = result.key(),
+                            reason = missing
+                        );
+                        writeln!(shown, "{skipped}\n").unwrap()
+                    }
+                    // Shown at the end, see `Summary::failures`
+                    (Err(_), None) => return Ok(()),
+                    (Ok(out), None) => {
+                        write!(shown, "{}", out.render(out.severity(&result.config))).unwrap();
+                        match changes {
+                            Some(changes) if !changes.is_empty() => {
+                                let changes =
+                                    t!("Changed: {changes}", changes = changes.join(", "));
+                                writeln!(shown, "{}\n", color::paint("35;1", changes)).unwrap();
+                            }
+                            _ => writeln!(shown).unwrap(),
+                        }
+                    }
+                }
+                self.shown.push((result.index, shown));
+            }
+            Event::Finished(summary) => {
+                // Clearing the screen and moving the cursor to the top
+                print!("\x1b[2J\x1b[H");
+                let header = t!(
+                    "Every {interval}s, press Ctrl-C to stop",
+                    interval = self.interval
+                );
+                println!("{}\n", color::paint("90", header));
+                self.shown.sort_by_key(|(index, _)| *index);
+                for (_, shown) in &self.shown {
+                    print!("{shown}");
+                }
+                if self.quiet {
+                    println!("{}", summary.short());
+                } else {
+                    println!("{summary}");
+                }
+                if let Some(failures) = summary.failures() {
+                    print!("{failures}");
+                }
+
+                self.prev = std::mem::take(&mut self.current);
+            }
+            Event::Tick | Event::FixStarted(_) | Event::FixApplied(_) | Event::Interrupted(_) => {}
+        }
+
+        Ok(())
+    }
+}